@@ -1,13 +1,74 @@
+pub mod accept_admin;
+pub mod approve_multisig_action;
+pub mod approve_vestee_wallet_change;
+pub mod cancel_admin_action;
+pub mod cancel_multisig_action;
+pub mod change_multisig;
 pub mod change_vestee_wallet;
 pub mod close_vesting_schedule;
+pub mod create_and_fund_vesting_schedule;
+pub mod create_deterministic_vesting_schedule;
+pub mod create_multisig;
+pub mod create_registry_page;
 pub mod create_vesting_schedule;
+pub mod create_vesting_schedule_v2;
+pub mod execute_admin_action;
+pub mod execute_multisig_action;
+pub mod freeze_vesting;
 pub mod fund_vesting_vault;
+pub mod invoke_multisig_action;
+pub mod migrate_vesting;
+pub mod propose_admin;
+pub mod propose_multisig_action;
+pub mod queue_admin_action;
+pub mod refund_surplus;
+pub mod request_vestee_wallet_change;
+pub mod rotate_admin_batch;
+pub mod set_blackout_windows;
+pub mod set_roles;
+pub mod set_timelock;
+pub mod set_vestee_operator;
+pub mod set_withdrawal_delegate;
+pub mod sweep_unclaimed_tokens;
+pub mod sync_vault;
+pub mod unfreeze_vesting;
 pub mod update_vested_tokens;
+pub mod withdraw_claimable_balance;
+pub mod withdraw_max_vested_tokens;
 pub mod withdraw_vested_tokens;
 
+pub use accept_admin::*;
+pub use approve_multisig_action::*;
+pub use approve_vestee_wallet_change::*;
+pub use cancel_admin_action::*;
+pub use cancel_multisig_action::*;
+pub use change_multisig::*;
 pub use change_vestee_wallet::*;
 pub use close_vesting_schedule::*;
+pub use create_and_fund_vesting_schedule::*;
+pub use create_deterministic_vesting_schedule::*;
+pub use create_multisig::*;
+pub use create_registry_page::*;
 pub use create_vesting_schedule::*;
+pub use execute_admin_action::*;
+pub use execute_multisig_action::*;
+pub use freeze_vesting::*;
 pub use fund_vesting_vault::*;
+pub use invoke_multisig_action::*;
+pub use migrate_vesting::*;
+pub use propose_admin::*;
+pub use propose_multisig_action::*;
+pub use queue_admin_action::*;
+pub use refund_surplus::*;
+pub use request_vestee_wallet_change::*;
+pub use rotate_admin_batch::*;
+pub use set_blackout_windows::*;
+pub use set_roles::*;
+pub use set_timelock::*;
+pub use set_vestee_operator::*;
+pub use set_withdrawal_delegate::*;
+pub use sweep_unclaimed_tokens::*;
+pub use sync_vault::*;
+pub use unfreeze_vesting::*;
 pub use update_vested_tokens::*;
 pub use withdraw_vested_tokens::*;
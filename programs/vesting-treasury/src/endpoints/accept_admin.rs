@@ -0,0 +1,30 @@
+//! Second step of handing over the admin role of a [`Vesting`] account. The
+//! key nominated with `propose_admin` signs to become the admin.
+//!
+//! If the vesting account is listed in the registries, the page which lists
+//! it and a page of the registry of the new admin are passed as remaining
+//! accounts, and the listing moves to the latter, see [`RegistryMove`].
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, AcceptAdmin<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    let previous_admin = accs.vesting.accept_admin(&accs.pending_admin.key())?;
+    RegistryMove::follow_admin(&accs.vesting, previous_admin, ctx.remaining_accounts)?;
+
+    emit!(AdminChanged {
+        vesting: accs.vesting.key(),
+        previous_admin,
+        new_admin: accs.pending_admin.key(),
+    });
+
+    Ok(())
+}
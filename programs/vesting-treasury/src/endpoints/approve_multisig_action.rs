@@ -0,0 +1,23 @@
+//! A member of a [`Multisig`] approves a [`MultisigProposal`].
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApproveMultisigAction<'info> {
+    pub member: Signer<'info>,
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key()
+        @ err::acc("Proposal does not belong to the provided multisig"),
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+}
+
+pub fn handle(ctx: Context<ApproveMultisigAction>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.proposal.approve(&accs.multisig, &accs.member.key())?;
+
+    Ok(())
+}
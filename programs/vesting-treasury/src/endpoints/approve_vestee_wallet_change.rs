@@ -0,0 +1,54 @@
+//! The wallet manager of a [`Vesting`] account approves the new vestee
+//! wallet requested by the owner of the current one with
+//! `request_vestee_wallet_change`. The approval only applies the wallet
+//! chosen by the vestee, hence it is not subject to the timelock. Since the
+//! request may come from a compromised key, approvals are blocked while the
+//! vesting account is frozen.
+//!
+//! If the owner of the vestee wallet changes and the vesting account is
+//! listed in the registries, the page which lists it and a page of the
+//! registry of the new owner are passed as remaining accounts, and the
+//! listing moves to the latter, see [`RegistryMove`].
+
+use crate::prelude::*;
+
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct ApproveVesteeWalletChange<'info> {
+    #[account(
+        constraint = wallet_manager.key() == vesting.wallet_manager()
+        @ err::acc("Vesting wallet manager does not match the provided signer")
+    )]
+    pub wallet_manager: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        constraint = vestee_wallet_new.mint == vesting.mint
+        @ err::acc("The new vestee wallet mint must be of correct mint")
+    )]
+    pub vestee_wallet_new: Account<'info, TokenAccount>,
+}
+
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, ApproveVesteeWalletChange<'info>>,
+) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.check_not_frozen()?;
+
+    let vestee_wallet_new = accs.vestee_wallet_new.key();
+    let previous_vestee = accs.vesting.vestee_wallet_owner;
+    let previous_vestee_wallet = accs
+        .vesting
+        .approve_vestee_wallet_change(&vestee_wallet_new, accs.vestee_wallet_new.owner)?;
+    RegistryMove::follow_vestee(&accs.vesting, previous_vestee, ctx.remaining_accounts)?;
+
+    emit!(VesteeWalletChanged {
+        vesting: accs.vesting.key(),
+        previous_vestee_wallet,
+        vestee_wallet: vestee_wallet_new,
+    });
+
+    Ok(())
+}
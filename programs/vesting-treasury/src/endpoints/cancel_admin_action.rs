@@ -0,0 +1,60 @@
+//! Cancels a [`QueuedAdminAction`] before it is executed. The owner of the
+//! vestee wallet, an operator allowed to do so, the guardian or the admin of
+//! the [`Vesting`] account can cancel, also while the vesting account is
+//! frozen. The rent is returned to whoever paid for the queued action.
+//!
+//! The vestee is the recorded owner of the vestee wallet, see
+//! [`Vesting::vestee`], such that they can cancel even if the vestee wallet
+//! was closed.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    pub canceller: Signer<'info>,
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        mut,
+        constraint = queued_action.vesting == vesting.key()
+        @ err::acc("Queued action does not belong to the vesting account"),
+        close = rent_receiver,
+    )]
+    pub queued_action: Account<'info, QueuedAdminAction>,
+    /// CHECK: Must be the payer of the queued action, who gets the rent back.
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == queued_action.payer
+        @ err::acc("Rent receiver must be the payer of the queued action"),
+    )]
+    pub rent_receiver: AccountInfo<'info>,
+}
+
+pub fn handle(ctx: Context<CancelAdminAction>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    // The guardian and the admin can cancel even if the vestee of an older
+    // vesting account is unknown, since no one signs as the default pubkey
+    let vestee = accs
+        .vesting
+        .vestee(ctx.remaining_accounts)
+        .unwrap_or_default();
+
+    let clock_ts = TimeStamp::current()?;
+    if !accs
+        .vesting
+        .is_action_cancel_authorized(&accs.canceller.key(), &vestee, clock_ts.time)
+    {
+        return Err(error!(err::acc(
+            "Only the vestee, their operator, the guardian or \
+            the admin can cancel a queued action"
+        )));
+    }
+
+    emit!(AdminActionCancelled {
+        vesting: accs.vesting.key(),
+        queued_action: accs.queued_action.key(),
+        canceller: accs.canceller.key(),
+    });
+
+    Ok(())
+}
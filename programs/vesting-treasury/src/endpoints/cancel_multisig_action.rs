@@ -0,0 +1,23 @@
+//! Closes a [`MultisigProposal`] which has not been executed, for example
+//! because it is no longer wanted or because the members of the multisig
+//! changed. Only the payer of the proposal can cancel it, and it gets the
+//! rent back.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelMultisigAction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = proposal.payer == payer.key()
+        @ err::acc("Only the payer of the proposal can cancel it"),
+        close = payer,
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+}
+
+pub fn handle(_ctx: Context<CancelMultisigAction>) -> Result<()> {
+    Ok(())
+}
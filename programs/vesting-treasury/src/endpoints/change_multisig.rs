@@ -0,0 +1,25 @@
+//! Replaces the members and the threshold of a [`Multisig`]. The endpoint
+//! must be signed by the multisig signer, hence the change itself must be
+//! approved by the current members. Pending proposals can no longer be
+//! approved nor executed afterwards.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct ChangeMultisig<'info> {
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        seeds = [Multisig::SIGNER_PDA_PREFIX, multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+pub fn handle(ctx: Context<ChangeMultisig>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.multisig.set_members(&members, threshold)?;
+
+    Ok(())
+}
@@ -1,7 +1,19 @@
-//! Changes vestee wallet in [`Vesting`] account. The admin should be able
-//! to change the vestee wallet such that in case the vestee wallet becomes
-//! compromised, the admin is able to target a different vestee wallet for a
-//! given vesting account.
+//! Changes vestee wallet in [`Vesting`] account. The wallet manager should be
+//! able to change the vestee wallet such that in case the vestee wallet
+//! becomes compromised, the wallet manager is able to target a different
+//! vestee wallet for a given vesting account. Unless set otherwise, the
+//! wallet manager is the admin.
+//!
+//! The owner of the vestee wallet can request a change as well, see
+//! `request_vestee_wallet_change`.
+//!
+//! If the vesting account has a timelock, the change must be queued with
+//! `queue_admin_action` instead.
+//!
+//! If the owner of the vestee wallet changes and the vesting account is
+//! listed in the registries, the page which lists it and a page of the
+//! registry of the new owner are passed as remaining accounts, and the
+//! listing moves to the latter, see [`RegistryMove`].
 
 use crate::prelude::*;
 
@@ -10,10 +22,10 @@ use anchor_spl::token::TokenAccount;
 #[derive(Accounts)]
 pub struct ChangeVesteeWallet<'info> {
     #[account(
-        constraint = admin.key() == vesting.admin
-        @ err::acc("Vesting admin does not match the provided signer")
+        constraint = wallet_manager.key() == vesting.wallet_manager()
+        @ err::acc("Vesting wallet manager does not match the provided signer")
     )]
-    pub admin: Signer<'info>,
+    pub wallet_manager: Signer<'info>,
     #[account(mut)]
     pub vesting: Account<'info, Vesting>,
     #[account(
@@ -23,15 +35,21 @@ pub struct ChangeVesteeWallet<'info> {
     pub vestee_wallet_new: Account<'info, TokenAccount>,
 }
 
-pub fn handle(ctx: Context<ChangeVesteeWallet>) -> Result<()> {
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, ChangeVesteeWallet<'info>>) -> Result<()> {
     let accs = ctx.accounts;
 
-    if accs.vesting.vestee_wallet == accs.vestee_wallet_new.key() {
-        return Err(error!(err::acc(
-            "The new vestee wallet is the same as the current vestee wallet"
-        )));
-    }
-    accs.vesting.vestee_wallet = accs.vestee_wallet_new.key();
+    accs.vesting.check_not_timelocked()?;
+    let previous_vestee = accs.vesting.vestee_wallet_owner;
+    let previous_vestee_wallet = accs
+        .vesting
+        .change_vestee_wallet(accs.vestee_wallet_new.key(), accs.vestee_wallet_new.owner)?;
+    RegistryMove::follow_vestee(&accs.vesting, previous_vestee, ctx.remaining_accounts)?;
+
+    emit!(VesteeWalletChanged {
+        vesting: accs.vesting.key(),
+        previous_vestee_wallet,
+        vestee_wallet: accs.vestee_wallet_new.key(),
+    });
 
     Ok(())
 }
@@ -1,34 +1,160 @@
 //! If the [`Vesting`] is fully vested and has no tokens that remain to be
 //! withdrawn, then the account is empty and can be closed without losing
 //! funds.
+//!
+//! If the claim deadline of the [`Vesting`] has passed, the vestee is no
+//! longer entitled to the tokens that were not withdrawn. In such case the
+//! account can be closed as soon as the unclaimed tokens have been swept
+//! from the vault.
+//!
+//! If the vesting account was listed in [`VestingRegistry`] pages when
+//! created, see [`Vesting::registered`], the pages which list it must be
+//! passed as remaining accounts, the admin page first, and the closed vesting
+//! account is removed from them. Since the listing follows the admin and the
+//! vestee, those are pages of the current admin and of the current owner of
+//! the vestee wallet. Vesting accounts which were never listed, such as those created before
+//! the registries were introduced, are closed without remaining accounts.
+//!
+//! The vault is closed as well. Any tokens left in it, such as dust or
+//! tokens sent to it directly, are first transferred by the vesting signer to
+//! a recovery wallet chosen by the closer, who is the admin unless set
+//! otherwise. The rent of both the vesting
+//! account and the vault goes to the rent receiver, such that whoever
+//! sponsored their creation can get their lamports back.
 
 use crate::prelude::*;
 
+use anchor_spl::token::{self, Token, TokenAccount};
+
 #[derive(Accounts)]
 pub struct CloseVestingSchedule<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    pub closer: Signer<'info>,
     #[account(
         mut,
-        constraint = vesting.admin == admin.key()
-            @ err::acc("Admin does not own this vesting account"),
-        close = admin,
+        constraint = vesting.closer() == closer.key()
+            @ err::acc("Vesting closer does not match the provided signer"),
+        close = rent_receiver,
     )]
     pub vesting: Account<'info, Vesting>,
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == vesting.vault.key()
+        @ err::acc("Vault input does not match the vault in the vesting account")
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Vesting::SIGNER_PDA_PREFIX, vesting.key().as_ref()],
+        bump
+    )]
+    pub vesting_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = recovery_wallet.mint == vesting.mint
+        @ err::acc("Recovery wallet must be of correct mint")
+    )]
+    pub recovery_wallet: Account<'info, TokenAccount>,
+    /// CHECK: Any account chosen by the closer can receive the rent of the
+    /// vesting account and of the vault.
+    #[account(mut)]
+    pub rent_receiver: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
-pub fn handle(ctx: Context<CloseVestingSchedule>) -> Result<()> {
-    let vesting = &ctx.accounts.vesting;
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, CloseVestingSchedule<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
+
+    let clock_ts = TimeStamp::current()?;
+    accs.check_can_close(clock_ts)?;
 
-    if vesting.cumulative_vested < vesting.total_vesting {
-        return Err(error!(err::acc("This vesting account is not fully vested")));
+    let vesting_key = accs.vesting.key();
+    if accs.vesting.registered {
+        RegistryPages::load(ctx.remaining_accounts)?
+            .ok_or_else(|| {
+                error!(err::acc(
+                    "The registry pages which list the vesting account must be passed as remaining accounts"
+                ))
+            })?
+            .remove(&vesting_key)?;
     }
 
-    if vesting.cumulative_vested > vesting.cumulative_withdrawn {
-        return Err(error!(err::acc(
-            "This vested tokens of this vesting account are not fully withdrawn"
-        )));
+    let signer_seeds = &[
+        Vesting::SIGNER_PDA_PREFIX,
+        &vesting_key.to_bytes()[..],
+        &[signer_bump_seed],
+    ];
+
+    // The token program only closes empty token accounts
+    if accs.vesting_vault.amount > 0 {
+        token::transfer(
+            accs.as_transfer_funds_from_vesting_vault_to_recovery_wallet_context()
+                .with_signer(&[&signer_seeds[..]]),
+            accs.vesting_vault.amount,
+        )?;
     }
 
+    token::close_account(
+        accs.as_close_vesting_vault_context()
+            .with_signer(&[&signer_seeds[..]]),
+    )?;
+
     Ok(())
 }
+
+impl<'info> CloseVestingSchedule<'info> {
+    fn check_can_close(&self, clock_ts: TimeStamp) -> Result<()> {
+        let vesting = &self.vesting;
+
+        if vesting.claimable_balance.amount > 0 {
+            return Err(error!(err::acc(
+                "The claimable balance must be withdrawn before closing the vesting account"
+            )));
+        }
+
+        if vesting.is_past_claim_deadline(clock_ts.time) {
+            if vesting.vault_balance.amount > 0 {
+                return Err(error!(err::acc(
+                    "The unclaimed tokens must be swept before closing the vesting account"
+                )));
+            }
+
+            return Ok(());
+        }
+
+        if vesting.cumulative_vested < vesting.total_vesting {
+            return Err(error!(err::acc("This vesting account is not fully vested")));
+        }
+
+        if vesting.cumulative_vested > vesting.cumulative_withdrawn {
+            return Err(error!(err::acc(
+                "This vested tokens of this vesting account are not fully withdrawn"
+            )));
+        }
+
+        Ok(())
+    }
+    fn as_transfer_funds_from_vesting_vault_to_recovery_wallet_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let cpi_accounts = token::Transfer {
+            from: self.vesting_vault.to_account_info(),
+            to: self.recovery_wallet.to_account_info(),
+            authority: self.vesting_signer.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    fn as_close_vesting_vault_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, token::CloseAccount<'info>> {
+        let cpi_accounts = token::CloseAccount {
+            account: self.vesting_vault.to_account_info(),
+            destination: self.rent_receiver.to_account_info(),
+            authority: self.vesting_signer.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
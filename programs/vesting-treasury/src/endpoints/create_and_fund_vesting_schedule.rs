@@ -0,0 +1,146 @@
+//! Initializes new [`Vesting`] account and funds its vault in a single
+//! instruction, such that the schedule is never visibly unfunded. The
+//! accounts and arguments are the same as in `create_vesting_schedule`, the
+//! schedule is validated like in `create_vesting_schedule_v2`, see
+//! [`Vesting::set_schedule_params`], and the initial funding is accounted for
+//! in the same way as in `fund_vesting_vault`.
+//!
+//! The initial funding amount cannot exceed the total vesting amount, the
+//! vault can be topped up with `fund_vesting_vault` afterwards.
+
+use crate::prelude::*;
+
+use crate::endpoints::create_vesting_schedule::{VestingInit, VestingVaultInit};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CreateAndFundVestingSchedule<'info> {
+    pub admin: Signer<'info>,
+    /// Pays the rent of the new accounts, such that the admin key doesn't
+    /// need to hold any lamports.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Vesting::space()
+    )]
+    pub vesting: Account<'info, Vesting>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Vesting::SIGNER_PDA_PREFIX, vesting.key().as_ref()],
+        bump
+    )]
+    pub vesting_signer: AccountInfo<'info>,
+    /// CHECK: The handler checks the address against the chosen layout and
+    /// creates the token account, see [`VestingVaultInit`].
+    #[account(mut)]
+    pub vesting_vault: AccountInfo<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        constraint = vestee_wallet.mint == mint.key()
+        @ err::acc("Vestee wallet must be of correct mint")
+    )]
+    pub vestee_wallet: Account<'info, TokenAccount>,
+    pub wallet_authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = funding_wallet.mint == mint.key()
+        @ err::acc("Funding wallet must be of correct mint")
+    )]
+    pub funding_wallet: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: UNSAFE_CODES.md#token
+    pub rent: AccountInfo<'info>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateAndFundVestingSchedule<'info>>,
+    vesting_amount: TokenAmount,
+    start_ts: TimeStamp,
+    cliff_periods: u64,
+    total_periods: u64,
+    period_type: u32,
+    options: VestingOptions,
+    funding_amount: TokenAmount,
+) -> Result<()> {
+    let accs = ctx.accounts;
+
+    if funding_amount > vesting_amount {
+        return Err(error!(err::arg(
+            "The initial funding amount cannot exceed the total vesting amount"
+        )));
+    }
+
+    let clock_ts = TimeStamp::current()?;
+    accs.vesting.set_schedule_params(
+        ScheduleParams::new(
+            vesting_amount,
+            start_ts,
+            cliff_periods,
+            total_periods,
+            period_type,
+        )?,
+        clock_ts.time,
+    )?;
+
+    let registry_pages = RegistryPages::load(ctx.remaining_accounts)?;
+    accs.vesting_init().init(options, registry_pages)?;
+
+    msg!("Funding vesting vault");
+
+    token::transfer(
+        accs.as_transfer_funds_from_funding_wallet_to_vault_context(),
+        funding_amount.amount,
+    )?;
+
+    accs.vesting.record_funding(funding_amount)?;
+
+    emit!(VestingVaultFunded {
+        vesting: accs.vesting.key(),
+        funder: accs.wallet_authority.key(),
+        funding_wallet: accs.funding_wallet.key(),
+        amount: funding_amount,
+    });
+
+    Ok(())
+}
+
+impl<'info> CreateAndFundVestingSchedule<'info> {
+    fn vesting_init(&mut self) -> VestingInit<'_, 'info> {
+        let vault = VestingVaultInit {
+            payer: self.payer.to_account_info(),
+            vesting: self.vesting.key(),
+            vesting_signer: self.vesting_signer.to_account_info(),
+            vesting_vault: self.vesting_vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        };
+
+        VestingInit {
+            admin: self.admin.key(),
+            vesting: &mut self.vesting,
+            vestee_wallet: &self.vestee_wallet,
+            vault,
+        }
+    }
+
+    fn as_transfer_funds_from_funding_wallet_to_vault_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let cpi_accounts = token::Transfer {
+            from: self.funding_wallet.to_account_info(),
+            to: self.vesting_vault.to_account_info(),
+            authority: self.wallet_authority.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
@@ -0,0 +1,116 @@
+//! Initializes new [`Vesting`] account at a program derived address, as
+//! opposed to `create_vesting_schedule` which initializes it at the address
+//! of a fresh keypair. The address is derived from the admin, the mint, the
+//! vestee wallet and a grant id chosen by the caller, which allows
+//! integrators to find the vesting account of a grant offline. Creating the
+//! same grant twice fails because the account already exists.
+//!
+//! The vesting signer and the vesting vault are derived from the address of
+//! the [`Vesting`] account in the same way as in `create_vesting_schedule`,
+//! and the vesting account is listed in the registries in the same way too.
+//! The vault layout is chosen with the option `associated_vault` as well.
+//! The schedule is validated like in `create_vesting_schedule_v2`, see
+//! [`Vesting::set_schedule_params`].
+
+use crate::prelude::*;
+
+use crate::endpoints::create_vesting_schedule::{VestingInit, VestingVaultInit};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateDeterministicVestingSchedule<'info> {
+    pub admin: Signer<'info>,
+    /// Pays the rent of the new accounts, such that the admin key doesn't
+    /// need to hold any lamports.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Vesting::space(),
+        seeds = [
+            Vesting::GRANT_PDA_PREFIX,
+            admin.key().as_ref(),
+            mint.key().as_ref(),
+            vestee_wallet.key().as_ref(),
+            grant_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub vesting: Account<'info, Vesting>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Vesting::SIGNER_PDA_PREFIX, vesting.key().as_ref()],
+        bump
+    )]
+    pub vesting_signer: AccountInfo<'info>,
+    /// CHECK: The handler checks the address against the chosen layout and
+    /// creates the token account, see [`VestingVaultInit`].
+    #[account(mut)]
+    pub vesting_vault: AccountInfo<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        constraint = vestee_wallet.mint == mint.key()
+        @ err::acc("Vestee wallet must be of correct mint")
+    )]
+    pub vestee_wallet: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: UNSAFE_CODES.md#token
+    pub rent: AccountInfo<'info>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateDeterministicVestingSchedule<'info>>,
+    _grant_id: u64,
+    vesting_amount: TokenAmount,
+    start_ts: TimeStamp,
+    cliff_periods: u64,
+    total_periods: u64,
+    period_type: u32,
+    options: VestingOptions,
+) -> Result<()> {
+    let accs = ctx.accounts;
+
+    let clock_ts = TimeStamp::current()?;
+    accs.vesting.set_schedule_params(
+        ScheduleParams::new(
+            vesting_amount,
+            start_ts,
+            cliff_periods,
+            total_periods,
+            period_type,
+        )?,
+        clock_ts.time,
+    )?;
+
+    let registry_pages = RegistryPages::load(ctx.remaining_accounts)?;
+    accs.vesting_init().init(options, registry_pages)
+}
+
+impl<'info> CreateDeterministicVestingSchedule<'info> {
+    fn vesting_init(&mut self) -> VestingInit<'_, 'info> {
+        let vault = VestingVaultInit {
+            payer: self.payer.to_account_info(),
+            vesting: self.vesting.key(),
+            vesting_signer: self.vesting_signer.to_account_info(),
+            vesting_vault: self.vesting_vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        };
+
+        VestingInit {
+            admin: self.admin.key(),
+            vesting: &mut self.vesting,
+            vestee_wallet: &self.vestee_wallet,
+            vault,
+        }
+    }
+}
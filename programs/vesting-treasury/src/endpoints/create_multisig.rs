@@ -0,0 +1,23 @@
+//! Creates a [`Multisig`], a set of keys of which `threshold` must approve
+//! any instruction signed by the multisig signer. To put a vesting schedule
+//! under the control of the set, the multisig signer is made its admin, or
+//! given one of its roles.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(init, payer = payer, space = Multisig::space())]
+    pub multisig: Account<'info, Multisig>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle(ctx: Context<CreateMultisig>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.multisig.set_members(&members, threshold)?;
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Creates a new page of the [`VestingRegistry`] of an admin or of a vestee.
+//! The endpoint is permissionless, since the registry only lists vesting
+//! accounts and any payer can sponsor the rent of a new page. The pages are
+//! filled in by the endpoints which create vesting schedules and must exist
+//! before those are called.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(kind: RegistryKind, owner: Pubkey, page: u32)]
+pub struct CreateRegistryPage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingRegistry::space(),
+        seeds = [
+            VestingRegistry::PREFIX,
+            kind.seed(),
+            owner.as_ref(),
+            page.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub registry: Account<'info, VestingRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle(
+    ctx: Context<CreateRegistryPage>,
+    kind: RegistryKind,
+    owner: Pubkey,
+    page: u32,
+) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.registry.kind = kind;
+    accs.registry.owner = owner;
+    accs.registry.page = page;
+
+    Ok(())
+}
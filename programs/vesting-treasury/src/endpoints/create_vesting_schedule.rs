@@ -1,18 +1,37 @@
 //! Initializes new [`Vesting`] account. After this call,
 //! the admin can fund the vesting vault such that the tokens
 //! become available to the beneficiary as they vest over time.
+//!
+//! If a page of the [`VestingRegistry`] of the admin and a page of the one of
+//! the owner of the vestee wallet are passed as remaining accounts, in this
+//! order, the new vesting account is listed in them. Without remaining
+//! accounts the vesting account is not listed, which keeps the accounts of
+//! this endpoint the same as before the registries were introduced.
+//!
+//! The vault is created either at the PDA derived from
+//! [`Vesting::VAULT_PREFIX`] or, if the option `associated_vault` is set, as
+//! the associated token account of the vesting signer, which explorers and
+//! wallets recognise as a standard token account of the schedule. The other
+//! endpoints only compare the vault with [`Vesting::vault`] and therefore
+//! accept either layout.
 
 use crate::prelude::*;
 
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct CreateVestingSchedule<'info> {
-    #[account(mut)]
     pub admin: Signer<'info>,
+    /// Pays the rent of the new accounts, such that the admin key doesn't
+    /// need to hold any lamports.
+    #[account(mut)]
+    pub payer: Signer<'info>,
     #[account(
         init,
-        payer = admin,
+        payer = payer,
         space = Vesting::space()
     )]
     pub vesting: Account<'info, Vesting>,
@@ -22,15 +41,9 @@ pub struct CreateVestingSchedule<'info> {
         bump
     )]
     pub vesting_signer: AccountInfo<'info>,
-    /// CHECK: UNSAFE_CODES.md#token
-    #[account(
-        init,
-        payer = admin,
-        space = TokenAccount::LEN,
-        owner = token_program.key(),
-        seeds = [Vesting::VAULT_PREFIX, vesting.key().as_ref()],
-        bump,
-    )]
+    /// CHECK: The handler checks the address against the chosen layout and
+    /// creates the token account, see [`VestingVaultInit`].
+    #[account(mut)]
     pub vesting_vault: AccountInfo<'info>,
     pub mint: Account<'info, Mint>,
     #[account(
@@ -39,13 +52,14 @@ pub struct CreateVestingSchedule<'info> {
     )]
     pub vestee_wallet: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     /// CHECK: UNSAFE_CODES.md#token
     pub rent: AccountInfo<'info>,
 }
 
-pub fn handle(
-    ctx: Context<CreateVestingSchedule>,
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateVestingSchedule<'info>>,
     vesting_amount: TokenAmount,
     start_ts: TimeStamp,
     cliff_periods: u64,
@@ -53,62 +67,196 @@ pub fn handle(
     // TODO: Add this to the documentation explaining
     // the mapping from u32 to PeriodType Enum
     period_type: u32,
+    options: VestingOptions,
 ) -> Result<()> {
-    if period_type > 2 {
-        return Err(error!(err::arg(
-            "The current contract version only supports \
-             vesting schedules with daily or monthly periods"
-        )));
-    }
+    let accs = ctx.accounts;
+    let registry_pages = RegistryPages::load(ctx.remaining_accounts)?;
+
+    accs.vesting.set_schedule(
+        vesting_amount,
+        start_ts,
+        cliff_periods,
+        total_periods,
+        period_type,
+    )?;
+
+    accs.vesting_init().init(options, registry_pages)
+}
+
+impl<'info> CreateVestingSchedule<'info> {
+    pub fn vesting_init(&mut self) -> VestingInit<'_, 'info> {
+        let vault = VestingVaultInit {
+            payer: self.payer.to_account_info(),
+            vesting: self.vesting.key(),
+            vesting_signer: self.vesting_signer.to_account_info(),
+            vesting_vault: self.vesting_vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        };
 
-    if cliff_periods > total_periods {
-        return Err(error!(err::arg(
-            "The number of cliff periods cannot be higher than total number of periods"
-        )));
+        VestingInit {
+            admin: self.admin.key(),
+            vesting: &mut self.vesting,
+            vestee_wallet: &self.vestee_wallet,
+            vault,
+        }
     }
+}
 
-    let vesting_signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
+/// Initializes a new [`Vesting`] account and its vault, shared by the create
+/// endpoints such that they only differ in how the schedule is set and in
+/// what they do once the vesting account exists.
+pub struct VestingInit<'a, 'info> {
+    pub admin: Pubkey,
+    pub vesting: &'a mut Account<'info, Vesting>,
+    pub vestee_wallet: &'a Account<'info, TokenAccount>,
+    pub vault: VestingVaultInit<'info>,
+}
 
-    let accs = ctx.accounts;
+impl<'a, 'info> VestingInit<'a, 'info> {
+    /// Initializes everything but the schedule of the new vesting account,
+    /// which must be set before because the options are validated against
+    /// it, lists it in the registry pages if any and creates the vault.
+    pub fn init(
+        self,
+        options: VestingOptions,
+        registry_pages: Option<RegistryPages<'info>>,
+    ) -> Result<()> {
+        let vesting = self.vesting;
 
-    accs.vesting.admin = accs.admin.key();
-    accs.vesting.vestee_wallet = accs.vestee_wallet.key();
-    accs.vesting.mint = accs.mint.key();
-    accs.vesting.vault = accs.vesting_vault.key();
+        vesting.version = Vesting::CURRENT_VERSION;
+        vesting.admin = self.admin;
+        vesting.vestee_wallet = self.vestee_wallet.key();
+        vesting.vestee_wallet_owner = self.vestee_wallet.owner;
+        vesting.mint = self.vault.mint.key();
+        vesting.vault = self.vault.vesting_vault.key();
 
-    accs.vesting.total_vesting = vesting_amount;
+        if let Some(registry_pages) = registry_pages {
+            registry_pages.insert(vesting.key(), self.admin, self.vestee_wallet.owner)?;
+            vesting.registered = true;
+        }
 
-    accs.vesting.start_ts = start_ts;
-    accs.vesting.total_periods = total_periods;
-    accs.vesting.cliff_periods = cliff_periods;
-    accs.vesting.period_type = PeriodType::from_u32(period_type)?;
+        vesting.set_options(options, &self.vestee_wallet.owner)?;
 
-    msg!("Initializing vesting vault");
+        msg!("Initializing vesting vault");
 
-    let signer_seed = &[
-        Vesting::SIGNER_PDA_PREFIX,
-        &accs.vesting.key().to_bytes()[..],
-        &[vesting_signer_bump_seed],
-    ];
-    token::initialize_account(
-        accs.as_init_vesting_vault_context()
-            .with_signer(&[&signer_seed[..]]),
-    )?;
+        self.vault.create(options.associated_vault)
+    }
+}
 
-    Ok(())
+/// The accounts needed to create the vault of a new [`Vesting`] account,
+/// shared by the create endpoints.
+pub struct VestingVaultInit<'info> {
+    pub payer: AccountInfo<'info>,
+    pub vesting: Pubkey,
+    pub vesting_signer: AccountInfo<'info>,
+    pub vesting_vault: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
 }
 
-impl<'info> CreateVestingSchedule<'info> {
-    pub fn as_init_vesting_vault_context(
-        &self,
-    ) -> CpiContext<'_, '_, '_, 'info, token::InitializeAccount<'info>> {
+impl<'info> VestingVaultInit<'info> {
+    /// Creates the vault as the associated token account of the vesting
+    /// signer if `associated` is true, or at the PDA derived from
+    /// [`Vesting::VAULT_PREFIX`] otherwise.
+    pub fn create(self, associated: bool) -> Result<()> {
+        if associated {
+            self.create_associated()
+        } else {
+            self.create_at_pda()
+        }
+    }
+
+    fn create_associated(self) -> Result<()> {
+        let expected_vault =
+            get_associated_token_address(&self.vesting_signer.key(), &self.mint.key());
+        if self.vesting_vault.key() != expected_vault {
+            return Err(error!(err::acc(
+                "Vault must be the associated token account of the vesting signer"
+            )));
+        }
+
+        let cpi_accounts = associated_token::Create {
+            payer: self.payer,
+            associated_token: self.vesting_vault,
+            authority: self.vesting_signer,
+            mint: self.mint,
+            system_program: self.system_program,
+            token_program: self.token_program,
+            rent: self.rent,
+        };
+        associated_token::create(CpiContext::new(self.associated_token_program, cpi_accounts))
+    }
+
+    fn create_at_pda(self) -> Result<()> {
+        let (expected_vault, vault_bump_seed) = Pubkey::find_program_address(
+            &[Vesting::VAULT_PREFIX, self.vesting.as_ref()],
+            &crate::ID,
+        );
+        if self.vesting_vault.key() != expected_vault {
+            return Err(error!(err::acc(
+                "Vault must be the PDA derived from the vesting account"
+            )));
+        }
+
+        let vault_seed = &[
+            Vesting::VAULT_PREFIX,
+            self.vesting.as_ref(),
+            &[vault_bump_seed],
+        ];
+        let rent = Rent::get()?.minimum_balance(TokenAccount::LEN);
+        let rent_top_up = rent.saturating_sub(self.vesting_vault.lamports());
+        let accounts = [
+            self.payer.clone(),
+            self.vesting_vault.clone(),
+            self.system_program.clone(),
+        ];
+
+        // Like anchor's init, don't fail if someone sent lamports to the
+        // vault address beforehand.
+        if self.vesting_vault.lamports() == 0 {
+            invoke_signed(
+                &system_instruction::create_account(
+                    &self.payer.key(),
+                    &expected_vault,
+                    rent,
+                    TokenAccount::LEN as u64,
+                    &self.token_program.key(),
+                ),
+                &accounts,
+                &[&vault_seed[..]],
+            )?;
+        } else {
+            if rent_top_up > 0 {
+                invoke(
+                    &system_instruction::transfer(&self.payer.key(), &expected_vault, rent_top_up),
+                    &accounts,
+                )?;
+            }
+            invoke_signed(
+                &system_instruction::allocate(&expected_vault, TokenAccount::LEN as u64),
+                &accounts,
+                &[&vault_seed[..]],
+            )?;
+            invoke_signed(
+                &system_instruction::assign(&expected_vault, &self.token_program.key()),
+                &accounts,
+                &[&vault_seed[..]],
+            )?;
+        }
+
         let cpi_accounts = token::InitializeAccount {
-            mint: self.mint.to_account_info(),
-            authority: self.vesting_signer.to_account_info(),
-            rent: self.rent.to_account_info(),
-            account: self.vesting_vault.to_account_info(),
+            mint: self.mint,
+            authority: self.vesting_signer,
+            rent: self.rent,
+            account: self.vesting_vault,
         };
-        let cpi_program = self.token_program.to_account_info();
-        CpiContext::new(cpi_program, cpi_accounts)
+        token::initialize_account(CpiContext::new(self.token_program, cpi_accounts))
     }
 }
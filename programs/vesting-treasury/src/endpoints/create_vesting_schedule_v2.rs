@@ -0,0 +1,24 @@
+//! Initializes new [`Vesting`] account like `create_vesting_schedule` and
+//! with the same accounts, but takes the schedule as a typed
+//! [`ScheduleParams`] with a [`PeriodType`] instead of a raw `u32`. All the
+//! parameters are validated up front against the runtime clock, see
+//! [`Vesting::set_schedule_params`], and each failure returns a dedicated
+//! error.
+
+use crate::prelude::*;
+
+use crate::endpoints::create_vesting_schedule::CreateVestingSchedule;
+
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateVestingSchedule<'info>>,
+    params: ScheduleParams,
+    options: VestingOptions,
+) -> Result<()> {
+    let accs = ctx.accounts;
+    let registry_pages = RegistryPages::load(ctx.remaining_accounts)?;
+
+    let clock_ts = TimeStamp::current()?;
+    accs.vesting.set_schedule_params(params, clock_ts.time)?;
+
+    accs.vesting_init().init(options, registry_pages)
+}
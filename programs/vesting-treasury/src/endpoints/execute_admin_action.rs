@@ -0,0 +1,180 @@
+//! Executes a [`QueuedAdminAction`] once its delay has passed. Anyone can
+//! execute, after which the queued action is closed and its rent returned to
+//! its payer. The action fails if the key which queued it no longer holds its
+//! power, for example because the admin or a role changed meanwhile.
+//!
+//! Some actions need more accounts, which are passed as remaining accounts:
+//!
+//! - [`AdminAction::ChangeVesteeWallet`] takes the new vestee wallet, which
+//!   must be of the vesting mint, followed by the registry pages if the
+//!   listing of the vesting account moves, see [`RegistryMove`]
+//! - [`AdminAction::SweepUnclaimedTokens`] takes the vesting vault, the
+//!   vesting signer, the recovery wallet and the token program
+
+use crate::prelude::*;
+
+use crate::endpoints::sweep_unclaimed_tokens::UnclaimedTokensSweep;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        mut,
+        constraint = queued_action.vesting == vesting.key()
+        @ err::acc("Queued action does not belong to the vesting account"),
+        close = rent_receiver,
+    )]
+    pub queued_action: Account<'info, QueuedAdminAction>,
+    /// CHECK: Must be the payer of the queued action, who gets the rent back.
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == queued_action.payer
+        @ err::acc("Rent receiver must be the payer of the queued action"),
+    )]
+    pub rent_receiver: AccountInfo<'info>,
+}
+
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteAdminAction<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    let clock_ts = TimeStamp::current()?;
+    if !accs.queued_action.is_due(clock_ts.time) {
+        return Err(error!(err::acc(
+            "The delay of the queued action has not passed yet"
+        )));
+    }
+    accs.queued_action.check_authority(&accs.vesting)?;
+
+    let action = accs.queued_action.action;
+    match action {
+        AdminAction::ChangeVesteeWallet { vestee_wallet } => {
+            let (wallet_info, registry_accounts) =
+                ctx.remaining_accounts.split_first().ok_or_else(|| {
+                    error!(err::acc(
+                        "The new vestee wallet must be passed as a remaining account"
+                    ))
+                })?;
+            let vestee_wallet_new = Account::<TokenAccount>::try_from(wallet_info)?;
+            if vestee_wallet_new.key() != vestee_wallet {
+                return Err(error!(err::acc(
+                    "The new vestee wallet does not match the queued action"
+                )));
+            }
+            if vestee_wallet_new.mint != accs.vesting.mint {
+                return Err(error!(err::acc(
+                    "The new vestee wallet mint must be of correct mint"
+                )));
+            }
+
+            let previous_vestee = accs.vesting.vestee_wallet_owner;
+            let previous_vestee_wallet = accs
+                .vesting
+                .change_vestee_wallet(vestee_wallet, vestee_wallet_new.owner)?;
+            RegistryMove::follow_vestee(&accs.vesting, previous_vestee, registry_accounts)?;
+
+            emit!(VesteeWalletChanged {
+                vesting: accs.vesting.key(),
+                previous_vestee_wallet,
+                vestee_wallet,
+            });
+        }
+        AdminAction::ProposeAdmin { pending_admin } => {
+            accs.vesting.propose_admin(pending_admin);
+
+            emit!(AdminProposed {
+                vesting: accs.vesting.key(),
+                admin: accs.vesting.admin,
+                pending_admin,
+            });
+        }
+        AdminAction::SweepUnclaimedTokens { recovery_wallet } => {
+            sweep_unclaimed_tokens(
+                &mut accs.vesting,
+                recovery_wallet,
+                ctx.remaining_accounts,
+                clock_ts.time,
+            )?;
+        }
+        AdminAction::SetTimelock {
+            action_delay_secs,
+            guardian,
+        } => {
+            accs.vesting.set_timelock(action_delay_secs, guardian)?;
+
+            emit!(TimelockChanged {
+                vesting: accs.vesting.key(),
+                action_delay_secs,
+                guardian,
+            });
+        }
+        AdminAction::SetRoles { roles } => {
+            accs.vesting.roles = roles;
+
+            emit!(RolesChanged {
+                vesting: accs.vesting.key(),
+                roles,
+            });
+        }
+    }
+
+    emit!(AdminActionExecuted {
+        vesting: accs.vesting.key(),
+        queued_action: accs.queued_action.key(),
+        action,
+    });
+
+    Ok(())
+}
+
+fn sweep_unclaimed_tokens<'info>(
+    vesting: &mut Account<'info, Vesting>,
+    recovery_wallet: Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    clock_ts: i64,
+) -> Result<()> {
+    let (vault_info, signer_info, recovery_info, token_program_info) = match remaining_accounts {
+        [vault, signer, recovery, token_program, ..] => (vault, signer, recovery, token_program),
+        _ => {
+            return Err(error!(err::acc(
+                "The vesting vault, the vesting signer, the recovery wallet \
+                and the token program must be passed as remaining accounts"
+            )))
+        }
+    };
+
+    let vesting_vault = Account::<TokenAccount>::try_from(vault_info)?;
+    if vesting_vault.key() != vesting.vault {
+        return Err(error!(err::acc(
+            "Vault input does not match the vault in the vesting account"
+        )));
+    }
+
+    let (vesting_signer, signer_bump_seed) = Pubkey::find_program_address(
+        &[Vesting::SIGNER_PDA_PREFIX, vesting.key().as_ref()],
+        &crate::ID,
+    );
+    if *signer_info.key != vesting_signer {
+        return Err(error!(err::acc(
+            "Vesting signer input does not match the PDA of the vesting account"
+        )));
+    }
+
+    if *recovery_info.key != recovery_wallet {
+        return Err(error!(err::acc(
+            "The recovery wallet does not match the queued action"
+        )));
+    }
+
+    let token_program = Program::<Token>::try_from(token_program_info)?;
+
+    UnclaimedTokensSweep {
+        vesting_vault: vault_info.clone(),
+        vesting_signer: signer_info.clone(),
+        recovery_wallet: recovery_info.clone(),
+        token_program: token_program.to_account_info(),
+        signer_bump_seed,
+    }
+    .sweep(vesting, vesting_vault.amount, clock_ts)
+}
@@ -0,0 +1,92 @@
+//! Executes a [`MultisigProposal`] which enough members approved. The
+//! proposed instruction is invoked with the signature of the multisig signer,
+//! after which the proposal is closed and its rent returned to its payer.
+//! Anyone can execute an approved proposal.
+//!
+//! The accounts of the proposed instruction, together with the account of
+//! this program, are passed as remaining accounts.
+
+use crate::prelude::*;
+
+use anchor_lang::solana_program::program::invoke_signed;
+
+#[derive(Accounts)]
+pub struct ExecuteMultisigAction<'info> {
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Multisig::SIGNER_PDA_PREFIX, multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = proposal.multisig == multisig.key()
+        @ err::acc("Proposal does not belong to the provided multisig"),
+        close = rent_receiver,
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+    /// CHECK: Must be the payer of the proposal, who gets the rent back.
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == proposal.payer
+        @ err::acc("Rent receiver must be the payer of the proposal"),
+    )]
+    pub rent_receiver: AccountInfo<'info>,
+}
+
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteMultisigAction<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("multisig_signer").unwrap();
+
+    if accs.proposal.executed {
+        return Err(error!(err::acc("The proposal has already been executed")));
+    }
+
+    if !accs.proposal.is_approved(&accs.multisig)? {
+        return Err(error!(err::acc(
+            "The proposal has not been approved by enough members of the multisig"
+        )));
+    }
+
+    // Persisted before the invocation such that the proposed instruction
+    // cannot execute the proposal again
+    accs.proposal.executed = true;
+    accs.proposal.exit(&crate::ID)?;
+
+    invoke_as_multisig_signer(
+        &accs.multisig.key(),
+        signer_bump_seed,
+        &accs.multisig_signer,
+        &accs.proposal.accounts,
+        &accs.proposal.data,
+        ctx.remaining_accounts,
+    )
+}
+
+/// Invokes an instruction of this program with the signature of the
+/// multisig signer. The provided account infos must contain all the accounts
+/// of the instruction but the multisig signer, and the account of this
+/// program.
+pub(crate) fn invoke_as_multisig_signer<'info>(
+    multisig: &Pubkey,
+    signer_bump_seed: u8,
+    multisig_signer: &AccountInfo<'info>,
+    accounts: &[ProposalAccount],
+    data: &[u8],
+    account_infos: &[AccountInfo<'info>],
+) -> Result<()> {
+    let ix = multisig_instruction(multisig_signer.key, accounts, data);
+
+    let mut infos = account_infos.to_vec();
+    infos.push(multisig_signer.clone());
+
+    let signer_seeds = &[
+        Multisig::SIGNER_PDA_PREFIX,
+        &multisig.to_bytes()[..],
+        &[signer_bump_seed],
+    ];
+    invoke_signed(&ix, &infos, &[&signer_seeds[..]])?;
+
+    Ok(())
+}
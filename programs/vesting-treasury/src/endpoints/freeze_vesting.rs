@@ -0,0 +1,31 @@
+//! Freezes a [`Vesting`] account in an emergency, e.g. when the key of the
+//! vestee is known to be compromised. While frozen, withdrawals and the
+//! wallet changes requested by the vestee are blocked, whereas the tokens
+//! keep vesting. The admin can still change the vestee wallet. The admin or
+//! the guardian can freeze, and neither is subject to the timelock.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct FreezeVesting<'info> {
+    #[account(
+        constraint = vesting.is_freeze_authorized(&authority.key())
+        @ err::acc("Only the admin or the guardian can freeze or unfreeze the vesting account")
+    )]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<FreezeVesting>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.set_frozen(true)?;
+
+    emit!(VestingFrozen {
+        vesting: accs.vesting.key(),
+        authority: accs.authority.key(),
+    });
+
+    Ok(())
+}
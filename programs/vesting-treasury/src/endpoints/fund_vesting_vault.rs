@@ -3,6 +3,10 @@
 //! tokens of the vesting mint. We track the amount of tokens that the admin
 //! needs to deposit in vesting vault in order to fulfill the promises stated
 //! on the vesting schedule via the [`Vesting`] field [`unfunded_liability`].
+//!
+//! Anyone can fund the vault, and each contribution is recorded in the event
+//! [`VestingVaultFunded`]. Tokens funded above the remaining obligation of the
+//! schedule can be returned with `refund_surplus`.
 
 use crate::prelude::*;
 
@@ -37,12 +41,14 @@ pub fn handle(ctx: Context<FundVestingVault>, funding_amount: TokenAmount) -> Re
         funding_amount.amount,
     )?;
 
-    accs.vesting.vault_balance =
-        TokenAmount::new(accs.vesting.vault_balance.amount + funding_amount.amount);
+    accs.vesting.record_funding(funding_amount)?;
 
-    // Since more tokens are being added to the vault we need to update how
-    // much of the vested tokens is currently unfunded, if any
-    accs.vesting.update_unfunded_liability()?;
+    emit!(VestingVaultFunded {
+        vesting: accs.vesting.key(),
+        funder: accs.wallet_authority.key(),
+        funding_wallet: accs.funding_wallet.key(),
+        amount: funding_amount,
+    });
 
     Ok(())
 }
@@ -0,0 +1,51 @@
+//! Invokes an instruction of this program with the signature of the multisig
+//! signer of a [`Multisig`], approved by members who sign the same
+//! transaction. This avoids creating a [`MultisigProposal`] when enough
+//! members can sign at once.
+//!
+//! The accounts of the instruction, the account of this program and the
+//! signing members are passed as remaining accounts.
+
+use crate::prelude::*;
+
+use super::execute_multisig_action::invoke_as_multisig_signer;
+
+#[derive(Accounts)]
+pub struct InvokeMultisigAction<'info> {
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Multisig::SIGNER_PDA_PREFIX, multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_signer: AccountInfo<'info>,
+}
+
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, InvokeMultisigAction<'info>>,
+    accounts: Vec<ProposalAccount>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("multisig_signer").unwrap();
+
+    let signers = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|info| info.is_signer)
+        .map(|info| info.key);
+    if !accs.multisig.is_approved_by(signers) {
+        return Err(error!(err::acc(
+            "Not enough members of the multisig signed the transaction"
+        )));
+    }
+
+    invoke_as_multisig_signer(
+        &accs.multisig.key(),
+        signer_bump_seed,
+        &accs.multisig_signer,
+        &accounts,
+        &data,
+        ctx.remaining_accounts,
+    )
+}
@@ -0,0 +1,52 @@
+//! Upgrades a [`Vesting`] account created by an older version of the program
+//! to the current layout. The account is reallocated to [`Vesting::space`],
+//! the payer tops up its rent exemption if needed, and the version is set to
+//! [`Vesting::CURRENT_VERSION`]. The other endpoints cannot load the account
+//! until it has been migrated.
+//!
+//! The endpoint is permissionless, as the migration does not change any of
+//! the settings or balances of the vesting schedule.
+
+use crate::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+#[derive(Accounts)]
+pub struct MigrateVesting<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Accounts of older versions are too short to be loaded as
+    /// [`Vesting`], hence the handler checks the discriminator and
+    /// deserializes the data itself.
+    #[account(mut, owner = crate::ID)]
+    pub vesting: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle(ctx: Context<MigrateVesting>) -> Result<()> {
+    let accs = ctx.accounts;
+    let vesting_info = accs.vesting.to_account_info();
+
+    let vesting = Vesting::from_legacy_data(&vesting_info.try_borrow_data()?)?;
+
+    let space = Vesting::space();
+    let rent = Rent::get()?.minimum_balance(space);
+    let rent_top_up = rent.saturating_sub(vesting_info.lamports());
+    if rent_top_up > 0 {
+        invoke(
+            &system_instruction::transfer(&accs.payer.key(), &vesting_info.key(), rent_top_up),
+            &[
+                accs.payer.to_account_info(),
+                vesting_info.clone(),
+                accs.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    vesting_info.realloc(space, true)?;
+
+    let mut data = vesting_info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    vesting.try_serialize(&mut writer)?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! First step of handing over the admin role of a [`Vesting`] account. The
+//! admin nominates a new key, which only becomes the admin once it signs
+//! `accept_admin`. This way the role cannot be handed over to a key nobody
+//! controls. Nominating the default pubkey cancels a pending nomination.
+//!
+//! If the vesting account has a timelock, the nomination must be queued with
+//! `queue_admin_action` instead.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        constraint = admin.key() == vesting.admin
+        @ err::acc("Vesting admin does not match the provided signer")
+    )]
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<ProposeAdmin>, pending_admin: Pubkey) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.check_not_timelocked()?;
+    accs.vesting.propose_admin(pending_admin);
+
+    emit!(AdminProposed {
+        vesting: accs.vesting.key(),
+        admin: accs.admin.key(),
+        pending_admin,
+    });
+
+    Ok(())
+}
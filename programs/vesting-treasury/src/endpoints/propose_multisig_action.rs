@@ -0,0 +1,50 @@
+//! Proposes an instruction of this program to be signed by the multisig
+//! signer of a [`Multisig`]. The proposer must be a member, and their
+//! proposal counts as their approval. The other members approve it over time
+//! with `approve_multisig_action`, after which anyone can execute it with
+//! `execute_multisig_action`.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeMultisigAction<'info> {
+    pub proposer: Signer<'info>,
+    pub multisig: Account<'info, Multisig>,
+    #[account(init, payer = payer, space = MultisigProposal::space())]
+    pub proposal: Account<'info, MultisigProposal>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle(
+    ctx: Context<ProposeMultisigAction>,
+    accounts: Vec<ProposalAccount>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let accs = ctx.accounts;
+
+    if accounts.len() > MultisigProposal::MAX_ACCOUNTS {
+        return Err(error!(err::arg(format!(
+            "A proposed instruction can have at most {} accounts",
+            MultisigProposal::MAX_ACCOUNTS
+        ))));
+    }
+
+    if data.len() > MultisigProposal::MAX_DATA_LEN {
+        return Err(error!(err::arg(format!(
+            "A proposed instruction can have at most {} bytes of data",
+            MultisigProposal::MAX_DATA_LEN
+        ))));
+    }
+
+    accs.proposal.multisig = accs.multisig.key();
+    accs.proposal.payer = accs.payer.key();
+    accs.proposal.seqno = accs.multisig.seqno;
+    accs.proposal.accounts = accounts;
+    accs.proposal.data = data;
+    accs.proposal
+        .approve(&accs.multisig, &accs.proposer.key())?;
+
+    Ok(())
+}
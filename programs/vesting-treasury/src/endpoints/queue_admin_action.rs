@@ -0,0 +1,54 @@
+//! Queues an admin action on a [`Vesting`] account which has a timelock. The
+//! action can be executed by anyone with `execute_admin_action` once the
+//! delay of the vesting account has passed. Until then the vestee, the
+//! guardian or the admin can cancel it with `cancel_admin_action`, such that
+//! a compromised key cannot, for example, redirect the withdrawals at once.
+//!
+//! The signer must hold the power of the action, see [`AdminAction`].
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    pub authority: Signer<'info>,
+    pub vesting: Account<'info, Vesting>,
+    #[account(init, payer = payer, space = QueuedAdminAction::space())]
+    pub queued_action: Account<'info, QueuedAdminAction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+    let accs = ctx.accounts;
+
+    if action.authority(&accs.vesting) != accs.authority.key() {
+        return Err(error!(err::acc(
+            "Signer does not hold the power to queue this admin action"
+        )));
+    }
+
+    if !accs.vesting.is_timelocked() {
+        return Err(error!(err::acc(
+            "Vesting account has no timelock, the admin action takes effect immediately"
+        )));
+    }
+
+    let clock_ts = TimeStamp::current()?;
+    let eta = TimeStamp::new(clock_ts.time + accs.vesting.action_delay_secs);
+
+    accs.queued_action.vesting = accs.vesting.key();
+    accs.queued_action.authority = accs.authority.key();
+    accs.queued_action.payer = accs.payer.key();
+    accs.queued_action.eta = eta;
+    accs.queued_action.action = action;
+
+    emit!(AdminActionQueued {
+        vesting: accs.vesting.key(),
+        queued_action: accs.queued_action.key(),
+        action,
+        eta,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Anyone can fund the [`vesting_vault`], hence it can end up holding more
+//! tokens than the schedule will ever pay out. This endpoint allows the
+//! funder, by default the admin, to return such surplus to the funders or to
+//! themselves. Only the tokens
+//! above the remaining obligation of the schedule can be refunded, as per
+//! [`Vesting::get_surplus`], so the entitlement of the vestee is never
+//! touched.
+//!
+//! Tokens sent straight to the vault must be recorded with `sync_vault`
+//! before they can be refunded.
+
+use crate::prelude::*;
+
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RefundSurplus<'info> {
+    #[account(
+        constraint = funder.key() == vesting.funder()
+        @ err::acc("Vesting funder does not match the provided signer")
+    )]
+    pub funder: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == vesting.vault.key()
+        @ err::acc("Vault input does not match the vault in the vesting account")
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Vesting::SIGNER_PDA_PREFIX, vesting.key().as_ref()],
+        bump
+    )]
+    pub vesting_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = refund_wallet.mint == vesting.mint
+        @ err::acc("Refund wallet must be of correct mint")
+    )]
+    pub refund_wallet: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handle(ctx: Context<RefundSurplus>, refund_amount: TokenAmount) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
+
+    if refund_amount.amount > accs.vesting.get_surplus() {
+        return Err(error!(err::arg(
+            "The refund amount exceeds the surplus over the remaining obligation"
+        )));
+    }
+
+    let signer_seeds = &[
+        Vesting::SIGNER_PDA_PREFIX,
+        &accs.vesting.key().to_bytes()[..],
+        &[signer_bump_seed],
+    ];
+
+    token::transfer(
+        accs.as_transfer_funds_from_vesting_vault_to_refund_wallet_context()
+            .with_signer(&[&signer_seeds[..]]),
+        refund_amount.amount,
+    )?;
+
+    accs.vesting.vault_balance =
+        TokenAmount::new(accs.vesting.vault_balance.amount - refund_amount.amount);
+    accs.vesting.update_unfunded_liability()?;
+
+    emit!(SurplusRefunded {
+        vesting: accs.vesting.key(),
+        refund_wallet: accs.refund_wallet.key(),
+        amount: refund_amount,
+    });
+
+    Ok(())
+}
+
+impl<'info> RefundSurplus<'info> {
+    fn as_transfer_funds_from_vesting_vault_to_refund_wallet_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let cpi_accounts = token::Transfer {
+            from: self.vesting_vault.to_account_info(),
+            to: self.refund_wallet.to_account_info(),
+            authority: self.vesting_signer.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
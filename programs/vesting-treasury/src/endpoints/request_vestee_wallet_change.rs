@@ -0,0 +1,68 @@
+//! The owner of the current vestee wallet of a [`Vesting`] account requests
+//! to move to a new vestee wallet of the same mint. Depending on the policy
+//! [`Vesting::immediate_vestee_wallet_change`] the change applies at once, or
+//! awaits the approval of the wallet manager with
+//! `approve_vestee_wallet_change`. A new request replaces a pending one.
+//! Requests are blocked while the vesting account is frozen.
+//!
+//! The signer must be the recorded owner of the vestee wallet, see
+//! [`Vesting::vestee`], such that the vestee can move away from a wallet
+//! which was closed.
+//!
+//! If the owner of the vestee wallet changes and the vesting account is
+//! listed in the registries, the page which lists it and a page of the
+//! registry of the new owner are passed as remaining accounts when the change applies at once, and the
+//! listing moves to the latter, see [`RegistryMove`].
+
+use crate::prelude::*;
+
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct RequestVesteeWalletChange<'info> {
+    pub vestee: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        constraint = vestee_wallet_new.mint == vesting.mint
+        @ err::acc("The new vestee wallet mint must be of correct mint")
+    )]
+    pub vestee_wallet_new: Account<'info, TokenAccount>,
+}
+
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, RequestVesteeWalletChange<'info>>,
+) -> Result<()> {
+    let accs = ctx.accounts;
+
+    let previous_vestee = accs.vesting.vestee(ctx.remaining_accounts)?;
+    if previous_vestee != accs.vestee.key() {
+        return Err(error!(err::acc(
+            "Only the owner of the vestee wallet can request a change"
+        )));
+    }
+    accs.vesting.check_not_frozen()?;
+
+    let vestee_wallet_new = accs.vestee_wallet_new.key();
+    match accs
+        .vesting
+        .request_vestee_wallet_change(vestee_wallet_new, accs.vestee_wallet_new.owner)?
+    {
+        Some(previous_vestee_wallet) => {
+            RegistryMove::follow_vestee(&accs.vesting, previous_vestee, ctx.remaining_accounts)?;
+
+            emit!(VesteeWalletChanged {
+                vesting: accs.vesting.key(),
+                previous_vestee_wallet,
+                vestee_wallet: vestee_wallet_new,
+            });
+        }
+        None => emit!(VesteeWalletChangeRequested {
+            vesting: accs.vesting.key(),
+            vestee_wallet: accs.vesting.vestee_wallet,
+            requested_vestee_wallet: vestee_wallet_new,
+        }),
+    }
+
+    Ok(())
+}
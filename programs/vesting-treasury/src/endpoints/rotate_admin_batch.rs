@@ -0,0 +1,59 @@
+//! Rotates the admin key of many [`Vesting`] accounts in one transaction. The
+//! vesting accounts are passed as remaining accounts, and all of them must be
+//! administered by the current admin. Both the current and the new admin sign,
+//! which gives the same guarantee as `propose_admin` followed by
+//! `accept_admin` without having to call those for every schedule. Vesting
+//! accounts with a timelock cannot be rotated this way.
+//!
+//! Each vesting account which is listed in the registries is followed by the
+//! page which lists it and a page of the registry of the new admin, to which
+//! the listing moves, see [`RegistryMove`].
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct RotateAdminBatch<'info> {
+    pub admin: Signer<'info>,
+    pub new_admin: Signer<'info>,
+}
+
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, RotateAdminBatch<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    if ctx.remaining_accounts.is_empty() {
+        return Err(error!(err::acc(
+            "The vesting accounts to rotate must be passed as remaining accounts"
+        )));
+    }
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    while let Some((vesting_info, rest)) = remaining_accounts.split_first() {
+        if !vesting_info.is_writable {
+            return Err(error!(err::acc(
+                "Vesting accounts to rotate must be writable"
+            )));
+        }
+
+        let mut vesting = Account::<Vesting>::try_from(vesting_info)?;
+        if vesting.admin != accs.admin.key() {
+            msg!(
+                "Vesting account {} is not administered by the signer",
+                vesting.key()
+            );
+            return Err(error!(TreasuryError::VestingAdminMismatch));
+        }
+        vesting.check_not_timelocked()?;
+
+        let previous_admin = vesting.set_admin(accs.new_admin.key());
+        remaining_accounts = RegistryMove::follow_admin(&vesting, previous_admin, rest)?;
+        vesting.exit(&crate::ID)?;
+
+        emit!(AdminChanged {
+            vesting: vesting.key(),
+            previous_admin,
+            new_admin: accs.new_admin.key(),
+        });
+    }
+
+    Ok(())
+}
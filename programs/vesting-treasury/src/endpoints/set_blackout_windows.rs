@@ -0,0 +1,37 @@
+//! Sets the blackout windows of a [`Vesting`] account. During a blackout
+//! window the vested tokens cannot be withdrawn, which allows the admin to
+//! enforce trading restrictions on insiders around sensitive announcements.
+//! The vesting schedule itself is not affected and keeps accruing.
+//!
+//! The provided list replaces any windows previously set, therefore an empty
+//! list lifts all blackout windows.
+//!
+//! The windows are bounded in number and length, must not overlap, and must
+//! end before the claim deadline of the vesting account. Every change emits
+//! the event [`BlackoutWindowsSet`].
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetBlackoutWindows<'info> {
+    #[account(
+        constraint = admin.key() == vesting.admin
+        @ err::acc("Vesting admin does not match the provided signer")
+    )]
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<SetBlackoutWindows>, windows: Vec<BlackoutWindow>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.set_blackout_windows(&windows)?;
+
+    emit!(BlackoutWindowsSet {
+        vesting: accs.vesting.key(),
+        windows,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Sets the keys to which the admin of a [`Vesting`] account delegates some
+//! of its powers, see [`VestingRoles`]. The provided roles replace the
+//! previous ones, and any role set to the default pubkey goes back to the
+//! admin.
+//!
+//! If the vesting account has a timelock, the roles must be changed by
+//! queueing [`AdminAction::SetRoles`] with `queue_admin_action` instead, as
+//! the wallet manager and the revoker hold timelocked powers.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    #[account(
+        constraint = admin.key() == vesting.admin
+        @ err::acc("Vesting admin does not match the provided signer")
+    )]
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<SetRoles>, roles: VestingRoles) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.check_not_timelocked()?;
+    accs.vesting.roles = roles;
+
+    emit!(RolesChanged {
+        vesting: accs.vesting.key(),
+        roles,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Sets the initial timelock of a [`Vesting`] account which has none, that
+//! is the delay of its queued admin actions and the guardian who can cancel
+//! them. This way the admin of a schedule created without a timelock can put
+//! one in place.
+//!
+//! Once the vesting account has a timelock, it can only be changed by
+//! queueing [`AdminAction::SetTimelock`] with `queue_admin_action`.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTimelock<'info> {
+    #[account(
+        constraint = admin.key() == vesting.admin
+        @ err::acc("Vesting admin does not match the provided signer")
+    )]
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<SetTimelock>, action_delay_secs: i64, guardian: Pubkey) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.check_not_timelocked()?;
+    accs.vesting.set_timelock(action_delay_secs, guardian)?;
+
+    emit!(TimelockChanged {
+        vesting: accs.vesting.key(),
+        action_delay_secs,
+        guardian,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Adds, updates or removes an operator of the [`Vesting`] account, see
+//! [`VesteeOperator`]. Only the owner of the vestee wallet can manage the
+//! operators, except while the [`Vesting`] is frozen. An operator without
+//! any permissions is removed.
+//!
+//! The signer must be the recorded owner of the vestee wallet, see
+//! [`Vesting::vestee`], rather than whoever owns the token account now.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetVesteeOperator<'info> {
+    pub vestee: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<SetVesteeOperator>, operator: VesteeOperator) -> Result<()> {
+    let accs = ctx.accounts;
+
+    if accs.vesting.vestee(ctx.remaining_accounts)? != accs.vestee.key() {
+        return Err(error!(err::acc("Signer does not own the vestee wallet")));
+    }
+    accs.vesting.check_not_frozen()?;
+
+    let clock_ts = TimeStamp::current()?;
+    accs.vesting.set_operator(operator, clock_ts.time)?;
+
+    emit!(VesteeOperatorSet {
+        vesting: accs.vesting.key(),
+        operator,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Registers a key that can sign withdrawals on behalf of the vestee when the
+//! [`Vesting`] account is in the beneficiary only mode. Only the owner of the
+//! vestee wallet can set the delegate. Setting the default pubkey removes
+//! the delegate. Every change emits the event [`WithdrawalDelegateSet`].
+//! The delegate cannot be changed while the [`Vesting`] is frozen.
+//!
+//! The signer must be the recorded owner of the vestee wallet, see
+//! [`Vesting::vestee`], rather than whoever owns the token account now.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetWithdrawalDelegate<'info> {
+    pub vestee: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<SetWithdrawalDelegate>, delegate: Pubkey) -> Result<()> {
+    let accs = ctx.accounts;
+
+    if accs.vesting.vestee(ctx.remaining_accounts)? != accs.vestee.key() {
+        return Err(error!(err::acc("Signer does not own the vestee wallet")));
+    }
+    accs.vesting.check_not_frozen()?;
+
+    accs.vesting.withdrawal_delegate = delegate;
+
+    emit!(WithdrawalDelegateSet {
+        vesting: accs.vesting.key(),
+        delegate,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Once the claim deadline of a [`Vesting`] account has passed, the vestee is
+//! no longer entitled to the tokens that were not withdrawn. This endpoint
+//! allows the revoker, by default the admin, to sweep the whole balance of
+//! the [`vesting_vault`] to a recovery wallet of their choice, after which the
+//! vesting account can be closed. Only the [`Vesting::claimable_balance`],
+//! which the vestee already withdrew, stays in the vault. Every sweep emits
+//! the event [`VestingSwept`].
+//!
+//! The sweep is rejected while the vesting account is frozen, since the
+//! vestee could not withdraw in the meantime.
+//!
+//! If the vesting account has a timelock, the sweep must be queued with
+//! `queue_admin_action` instead.
+
+use crate::prelude::*;
+
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SweepUnclaimedTokens<'info> {
+    #[account(
+        constraint = revoker.key() == vesting.revoker()
+        @ err::acc("Vesting revoker does not match the provided signer")
+    )]
+    pub revoker: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        mut,
+        constraint = vesting_vault.key() == vesting.vault.key()
+        @ err::acc("Vault input does not match the vault in the vesting account")
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    /// CHECK: UNSAFE_CODES.md#signer
+    #[account(
+        seeds = [Vesting::SIGNER_PDA_PREFIX, vesting.key().as_ref()],
+        bump
+    )]
+    pub vesting_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = recovery_wallet.mint == vesting.mint
+        @ err::acc("Recovery wallet must be of correct mint")
+    )]
+    pub recovery_wallet: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handle(ctx: Context<SweepUnclaimedTokens>) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
+
+    accs.vesting.check_not_timelocked()?;
+
+    let clock_ts = TimeStamp::current()?;
+    UnclaimedTokensSweep {
+        vesting_vault: accs.vesting_vault.to_account_info(),
+        vesting_signer: accs.vesting_signer.to_account_info(),
+        recovery_wallet: accs.recovery_wallet.to_account_info(),
+        token_program: accs.token_program.to_account_info(),
+        signer_bump_seed,
+    }
+    .sweep(&mut accs.vesting, accs.vesting_vault.amount, clock_ts.time)
+}
+
+/// The accounts needed to sweep the unclaimed tokens of a [`Vesting`]
+/// account, shared by this endpoint and by `execute_admin_action`, which
+/// validate them beforehand.
+pub struct UnclaimedTokensSweep<'info> {
+    pub vesting_vault: AccountInfo<'info>,
+    pub vesting_signer: AccountInfo<'info>,
+    pub recovery_wallet: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub signer_bump_seed: u8,
+}
+
+impl<'info> UnclaimedTokensSweep<'info> {
+    /// Transfers the tokens to the recovery wallet, given the actual balance
+    /// of the vault, and emits [`VestingSwept`]. Fails unless the tokens can
+    /// be swept, see [`Vesting::check_can_sweep`].
+    pub fn sweep(
+        self,
+        vesting: &mut Account<'info, Vesting>,
+        vault_amount: u64,
+        clock_ts: i64,
+    ) -> Result<()> {
+        vesting.check_can_sweep(clock_ts)?;
+
+        let vesting_key = vesting.key();
+        let signer_seeds = &[
+            Vesting::SIGNER_PDA_PREFIX,
+            &vesting_key.to_bytes()[..],
+            &[self.signer_bump_seed],
+        ];
+        let recovery_wallet = self.recovery_wallet.key();
+        let cpi_accounts = token::Transfer {
+            from: self.vesting_vault,
+            to: self.recovery_wallet,
+            authority: self.vesting_signer,
+        };
+
+        // We sweep the actual balance of the vault rather than the tracked one
+        // such that no tokens are left behind in the vault, except for the
+        // claimable balance which the vestee already withdrew
+        let amount = vesting.get_sweepable_amount(vault_amount);
+        token::transfer(
+            CpiContext::new(self.token_program, cpi_accounts).with_signer(&[&signer_seeds[..]]),
+            amount,
+        )?;
+
+        vesting.vault_balance = TokenAmount::new(0);
+
+        emit!(VestingSwept {
+            vesting: vesting_key,
+            recovery_wallet,
+            amount: TokenAmount::new(amount),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! Tokens can be sent straight to the [`vesting_vault`] instead of through
+//! `fund_vesting_vault`, in which case the program doesn't know about them.
+//! This permissionless endpoint reads the actual balance of the vault, records
+//! any difference to [`Vesting::vault_balance`] as funding and recomputes the
+//! [`Vesting::unfunded_liability`]. Every sync emits the event
+//! [`VaultSynced`].
+
+use crate::prelude::*;
+
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SyncVault<'info> {
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+    #[account(
+        constraint = vesting_vault.key() == vesting.vault.key()
+        @ err::acc("Vault input does not match the vault in the vesting account")
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+}
+
+pub fn handle(ctx: Context<SyncVault>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    let previous_vault_balance = accs.vesting.vault_balance;
+    let difference = accs.vesting.sync_vault_balance(accs.vesting_vault.amount)?;
+    msg!(
+        "Vault balance changed by {} since the last sync",
+        difference
+    );
+
+    emit!(VaultSynced {
+        vesting: accs.vesting.key(),
+        previous_vault_balance,
+        vault_balance: accs.vesting.vault_balance,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! Unfreezes a [`Vesting`] account frozen with `freeze_vesting`, which
+//! unblocks withdrawals and the wallet changes requested by the vestee. The
+//! admin or the guardian can unfreeze.
+
+use crate::prelude::*;
+
+#[derive(Accounts)]
+pub struct UnfreezeVesting<'info> {
+    #[account(
+        constraint = vesting.is_freeze_authorized(&authority.key())
+        @ err::acc("Only the admin or the guardian can freeze or unfreeze the vesting account")
+    )]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Account<'info, Vesting>,
+}
+
+pub fn handle(ctx: Context<UnfreezeVesting>) -> Result<()> {
+    let accs = ctx.accounts;
+
+    accs.vesting.set_frozen(false)?;
+
+    emit!(VestingUnfrozen {
+        vesting: accs.vesting.key(),
+        authority: accs.authority.key(),
+    });
+
+    Ok(())
+}
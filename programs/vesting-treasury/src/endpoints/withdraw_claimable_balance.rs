@@ -0,0 +1,44 @@
+//! Pulls the [`Vesting::claimable_balance`], that is the withdrawn tokens
+//! which could not be delivered to the vestee wallet, from the
+//! [`vesting_vault`] to a vestee wallet which can receive them now. That is
+//! the vestee wallet after it was thawed or changed, or, if the [`Vesting`]
+//! records a beneficiary owner, any of its token accounts of the vesting
+//! mint.
+//!
+//! The accounts, including the remaining accounts, and the signer
+//! restrictions are the same as in `withdraw_vested_tokens`, and the endpoint
+//! is blocked while the [`Vesting`] is frozen. Since the tokens were already
+//! withdrawn, neither the blackout windows, the claim deadline nor the rate
+//! limit apply.
+
+use crate::prelude::*;
+
+use crate::endpoints::withdraw_vested_tokens::WithdrawVestedTokens;
+
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawVestedTokens<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
+
+    accs.vesting.check_not_frozen()?;
+
+    let (withdraw_authority, remaining_accounts) =
+        accs.split_withdraw_authority(ctx.remaining_accounts)?;
+    let vestee_wallet = accs.prepare_vestee_wallet(remaining_accounts)?;
+    if !vestee_wallet.can_receive {
+        return Err(error!(err::acc("The vestee wallet cannot receive tokens")));
+    }
+
+    let clock_ts = TimeStamp::current()?;
+    accs.check_withdraw_authority(withdraw_authority, &vestee_wallet.vestee, clock_ts)?;
+
+    let amount = accs.vesting.take_claimable_balance()?;
+    accs.transfer_to_vestee_wallet(amount, signer_bump_seed)?;
+
+    emit!(ClaimableBalanceWithdrawn {
+        vesting: accs.vesting.key(),
+        vestee_wallet: accs.vestee_wallet.key(),
+        amount,
+    });
+
+    Ok(())
+}
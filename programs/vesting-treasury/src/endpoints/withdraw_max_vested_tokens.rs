@@ -0,0 +1,53 @@
+//! Withdraws as many vested tokens as currently possible from the
+//! [`vesting_vault`] to the [`vestee_wallet`]. That is the smaller of the
+//! vested tokens not yet withdrawn and the tokens in the vault, further
+//! capped by what the rate limit allows, if any. This allows a single
+//! instruction to sweep everything claimable even when the vault is only
+//! partially funded.
+//!
+//! The amount actually withdrawn is reported via the
+//! [`VestedTokensWithdrawn`] event and as the return data of the instruction,
+//! encoded as a little endian `u64`.
+//!
+//! If nothing can be withdrawn, the endpoint succeeds without a transfer and
+//! without an event, and only reports zero as the return data, such that
+//! automation calling it on a schedule need not check the state first. None
+//! of the checks of `withdraw_vested_tokens` apply in that case.
+//!
+//! The accounts, including the remaining accounts, and restrictions are the
+//! same as in `withdraw_vested_tokens`.
+
+use crate::prelude::*;
+
+use crate::endpoints::withdraw_vested_tokens::WithdrawVestedTokens;
+
+use anchor_lang::solana_program::program::set_return_data;
+
+pub fn handle<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawVestedTokens<'info>>) -> Result<()> {
+    let accs = ctx.accounts;
+    let signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
+
+    let (withdraw_authority, remaining_accounts) =
+        accs.split_withdraw_authority(ctx.remaining_accounts)?;
+    let vestee_wallet = accs.prepare_vestee_wallet(remaining_accounts)?;
+
+    let clock_ts = TimeStamp::current()?;
+    accs.accrue_vested_tokens(clock_ts)?;
+
+    let withdraw_amount = accs.vesting.get_max_withdrawable(clock_ts.time);
+    if withdraw_amount.amount == 0 {
+        msg!("There are no vested tokens to withdraw");
+    } else {
+        accs.withdraw(
+            clock_ts,
+            withdraw_amount,
+            signer_bump_seed,
+            vestee_wallet,
+            withdraw_authority,
+        )?;
+    }
+
+    set_return_data(&withdraw_amount.amount.to_le_bytes());
+
+    Ok(())
+}
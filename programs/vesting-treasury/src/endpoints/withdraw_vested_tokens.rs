@@ -2,10 +2,44 @@
 //! the [`vestee_wallet`]. Whilst the endpoint is permissionless, only tokens
 //! that have been vested and subsequently funded by the administrator or
 //! any other agent, will be avaialble for transfer. The endpoint is made
-//! permissionless to more easily allow for automation.
+//! permissionless to more easily allow for automation, unless the
+//! [`Vesting`] is in the beneficiary only mode, in which case the withdrawal
+//! must be signed by the owner of the vestee wallet, by their delegate or by
+//! an operator allowed to withdraw. That signer is passed as the first
+//! remaining account, and only in this mode. In any case the tokens go to
+//! the vestee wallet.
+//!
+//! Withdrawals are rejected while the [`Vesting`] is frozen or any of its
+//! blackout windows is active, and for good once the claim deadline of the
+//! schedule has passed.
+//! On top of that, the amount withdrawn is subject to the rate limit of the
+//! [`Vesting`], if any.
+//!
+//! The vested tokens are brought up to date with the runtime clock before
+//! withdrawing, hence there is no need to call `update_vested_tokens` first.
+//!
+//! If the [`Vesting`] records a beneficiary owner, the tokens can go to any
+//! token account of the vesting mint owned by it. When that account does not
+//! exist yet, it must be the associated token account of the beneficiary
+//! owner, which is then created. The payer, the beneficiary owner, the
+//! vesting mint, the system program, the associated token program and the
+//! rent sysvar are passed as remaining accounts in that case, after the
+//! withdraw authority if any.
+//!
+//! If the vestee wallet cannot receive the tokens, because it was closed or
+//! frozen, is of another mint or is not owned by the beneficiary owner, the
+//! withdrawal does not fail. Instead the tokens stay in the vault as the
+//! [`Vesting::claimable_balance`], which the beneficiary later pulls with
+//! `withdraw_claimable_balance`, and the event [`VestedTokensEscrowed`] is
+//! emitted.
+//! If the [`Vesting`] records a beneficiary owner, only the vestee wallet and
+//! the associated token account of the beneficiary owner escrow like that,
+//! any other destination which cannot receive the tokens fails the
+//! withdrawal.
 
 use crate::prelude::*;
 
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
 use anchor_spl::token::{self, Token, TokenAccount};
 
 #[derive(Accounts)]
@@ -24,63 +58,303 @@ pub struct WithdrawVestedTokens<'info> {
         bump
     )]
     pub vesting_signer: AccountInfo<'info>,
-    #[account(
-        mut,
-        constraint = vestee_wallet.key() == vesting.vestee_wallet.key()
-        @ err::acc("Vestee wallet input does not match the \
-         vestee wallet in the vesting account")
-    )]
-    pub vestee_wallet: Account<'info, TokenAccount>,
+    /// CHECK: The vestee wallet or, if the vesting account records a
+    /// beneficiary owner, any of its token accounts of the vesting mint. See
+    /// [`WithdrawVestedTokens::prepare_vestee_wallet`].
+    #[account(mut)]
+    pub vestee_wallet: AccountInfo<'info>,
     pub token_program: Program<'info, Token>,
 }
 
-pub fn handle(ctx: Context<WithdrawVestedTokens>, withdraw_amount: TokenAmount) -> Result<()> {
+pub fn handle<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawVestedTokens<'info>>,
+    withdraw_amount: TokenAmount,
+) -> Result<()> {
     let accs = ctx.accounts;
     let signer_bump_seed = *ctx.bumps.get("vesting_signer").unwrap();
 
-    let liability = accs.vesting.get_current_liability();
+    let (withdraw_authority, remaining_accounts) =
+        accs.split_withdraw_authority(ctx.remaining_accounts)?;
+    let vestee_wallet = accs.prepare_vestee_wallet(remaining_accounts)?;
 
-    if withdraw_amount.amount > liability {
-        return Err(error!(err::arg(
-            "The amount of tokens to withdraw is bigger than\
-            the amount of vested tokens to be withdrawn"
-        )));
-    }
+    let clock_ts = TimeStamp::current()?;
+    accs.accrue_vested_tokens(clock_ts)?;
 
-    if withdraw_amount.amount > accs.vesting.vault_balance.amount {
-        return Err(error!(err::arg(
-            "The amount of tokens to withdraw is higher \
-            than the amount of tokens currently in vault, \
-            it seems the vault is partially unfunded"
-        )));
-    }
+    accs.withdraw(
+        clock_ts,
+        withdraw_amount,
+        signer_bump_seed,
+        vestee_wallet,
+        withdraw_authority,
+    )
+}
 
-    // make token transfers from vesting token vault to vestee wallet
-    let signer_seeds = &[
-        Vesting::SIGNER_PDA_PREFIX,
-        &accs.vesting.key().to_bytes()[..],
-        &[signer_bump_seed],
-    ];
-
-    token::transfer(
-        accs.as_transfer_funds_from_vesting_vault_to_vestee_wallet_context()
-            .with_signer(&[&signer_seeds[..]]),
-        withdraw_amount.amount,
-    )?;
-
-    // Update the vault balance and cumulative withdrawn state
-    // Note that we don't need to update unfunded liabilities because the delta
-    // by which the vault balance decreases is offset by the delta by which the
-    // cumulative withdrawn amount increases (same delta)
-    accs.vesting.vault_balance =
-        TokenAmount::new(accs.vesting.vault_balance.amount - withdraw_amount.amount);
-
-    accs.vesting.cumulative_withdrawn =
-        TokenAmount::new(accs.vesting.cumulative_withdrawn.amount + withdraw_amount.amount);
-
-    Ok(())
+/// The vestee wallet of a withdrawal, as checked by
+/// [`WithdrawVestedTokens::prepare_vestee_wallet`].
+#[derive(Clone, Copy, Debug)]
+pub struct PreparedVesteeWallet {
+    /// The key which signs withdrawals as the vestee, that is the
+    /// beneficiary owner or the recorded owner of the vestee wallet, see
+    /// [`Vesting::vestee`]. The default pubkey if the vestee wallet of an
+    /// account which predates the record was closed.
+    pub vestee: Pubkey,
+    /// False if the vestee wallet is closed, frozen, of another mint or not
+    /// owned by the beneficiary owner or the recorded owner.
+    pub can_receive: bool,
 }
+
 impl<'info> WithdrawVestedTokens<'info> {
+    /// In the beneficiary only mode, takes the withdraw authority off the
+    /// remaining accounts, where it must come first and sign. Otherwise
+    /// withdrawals need no signer and the remaining accounts are returned as
+    /// they are.
+    pub fn split_withdraw_authority<'a>(
+        &self,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(Option<Pubkey>, &'a [AccountInfo<'info>])> {
+        if !self.vesting.beneficiary_only {
+            return Ok((None, remaining_accounts));
+        }
+
+        match remaining_accounts.split_first() {
+            Some((withdraw_authority, rest)) if withdraw_authority.is_signer => {
+                Ok((Some(withdraw_authority.key()), rest))
+            }
+            _ => Err(error!(err::acc(
+                "Withdrawals from this vesting account must be signed by the \
+                withdraw authority passed as the first remaining account"
+            ))),
+        }
+    }
+
+    /// Checks the given vestee wallet, after creating it as the associated
+    /// token account of the beneficiary owner if it does not exist yet and
+    /// the remaining accounts to do so are given, and tells whether it can
+    /// receive tokens.
+    pub fn prepare_vestee_wallet(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<PreparedVesteeWallet> {
+        self.vesting
+            .check_withdrawal_destination(&self.vestee_wallet.key())?;
+
+        if self.vesting.withdraws_to_owner()
+            && self.vestee_wallet.data_is_empty()
+            && !remaining_accounts.is_empty()
+        {
+            self.create_vestee_wallet(remaining_accounts)?;
+        }
+
+        let vestee_wallet = Account::<TokenAccount>::try_from(&self.vestee_wallet).ok();
+
+        let vestee = if self.vesting.withdraws_to_owner() {
+            self.vesting.beneficiary_owner
+        } else if vestee_wallet.is_some() {
+            self.vesting
+                .vestee(std::slice::from_ref(&self.vestee_wallet))?
+        } else {
+            // A closed wallet of an account which predates the record has
+            // no owner left to sign
+            self.vesting.vestee_wallet_owner
+        };
+
+        let can_receive = vestee_wallet.map_or(false, |wallet| {
+            !wallet.is_frozen()
+                && self
+                    .vesting
+                    .can_receive_withdrawals(&wallet.mint, &wallet.owner)
+        });
+
+        Ok(PreparedVesteeWallet {
+            vestee,
+            can_receive,
+        })
+    }
+
+    /// Fails unless the withdraw authority, as returned by
+    /// [`WithdrawVestedTokens::split_withdraw_authority`], is allowed to
+    /// withdraw on behalf of the given vestee. Checked here rather than in
+    /// the account constraints because operators expire with the clock.
+    pub fn check_withdraw_authority(
+        &self,
+        withdraw_authority: Option<Pubkey>,
+        vestee: &Pubkey,
+        clock_ts: TimeStamp,
+    ) -> Result<()> {
+        let is_authorized = withdraw_authority.map_or(!self.vesting.beneficiary_only, |signer| {
+            self.vesting
+                .is_withdrawal_authorized(&signer, vestee, clock_ts.time)
+        });
+
+        if !is_authorized {
+            return Err(error!(err::acc(
+                "Withdrawals from this vesting account must be \
+                signed by the vestee, their delegate or an operator"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn create_vestee_wallet(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let (payer, owner, mint, system_program, associated_token_program, rent) =
+            match remaining_accounts {
+                [payer, owner, mint, system_program, associated_token_program, rent, ..] => (
+                    payer,
+                    owner,
+                    mint,
+                    system_program,
+                    associated_token_program,
+                    rent,
+                ),
+                _ => {
+                    return Err(error!(err::acc(
+                        "The payer, the beneficiary owner, the mint, the system program, \
+                        the associated token program and the rent sysvar must be passed \
+                        as remaining accounts to create the vestee wallet"
+                    )))
+                }
+            };
+
+        let expected_wallet =
+            get_associated_token_address(&self.vesting.beneficiary_owner, &self.vesting.mint);
+        if self.vestee_wallet.key() != expected_wallet {
+            return Err(error!(err::acc(
+                "A vestee wallet which does not exist yet must be the \
+                associated token account of the beneficiary owner"
+            )));
+        }
+
+        if owner.key() != self.vesting.beneficiary_owner || mint.key() != self.vesting.mint {
+            return Err(error!(err::acc(
+                "The beneficiary owner or the mint does not match the vesting account"
+            )));
+        }
+
+        let associated_token_program =
+            Program::<AssociatedToken>::try_from(associated_token_program)?;
+
+        msg!("Creating the associated token account of the beneficiary owner");
+
+        let cpi_accounts = associated_token::Create {
+            payer: payer.clone(),
+            associated_token: self.vestee_wallet.clone(),
+            authority: owner.clone(),
+            mint: mint.clone(),
+            system_program: system_program.clone(),
+            token_program: self.token_program.to_account_info(),
+            rent: rent.clone(),
+        };
+        associated_token::create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            cpi_accounts,
+        ))
+    }
+
+    /// Brings the amount of vested tokens up to date with the given clock
+    /// such that the vestee does not need to call `update_vested_tokens`
+    /// before withdrawing.
+    pub fn accrue_vested_tokens(&mut self, clock_ts: TimeStamp) -> Result<()> {
+        self.vesting.update_vested_tokens(clock_ts.time)?;
+
+        // Since more tokens may be vested we need to update how much of
+        // those vested tokens is currently unfunded
+        self.vesting.update_unfunded_liability()
+    }
+
+    /// Transfers `withdraw_amount` from the vesting vault to the vestee
+    /// wallet, after checking it against the vested tokens, the vault balance
+    /// and the restrictions of the [`Vesting`] account. If the vestee wallet
+    /// cannot receive tokens, the amount is added to the claimable balance
+    /// instead, such that the withdrawal does not get stuck.
+    pub fn withdraw(
+        &mut self,
+        clock_ts: TimeStamp,
+        withdraw_amount: TokenAmount,
+        signer_bump_seed: u8,
+        vestee_wallet: PreparedVesteeWallet,
+        withdraw_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        self.vesting.check_not_frozen()?;
+        self.check_withdraw_authority(withdraw_authority, &vestee_wallet.vestee, clock_ts)?;
+        if !vestee_wallet.can_receive {
+            self.vesting
+                .check_escrow_destination(&self.vestee_wallet.key())?;
+        }
+
+        if self.vesting.is_in_blackout(clock_ts.time) {
+            return Err(error!(TreasuryError::WithdrawalInBlackoutWindow));
+        }
+
+        if self.vesting.is_past_claim_deadline(clock_ts.time) {
+            return Err(error!(TreasuryError::ClaimDeadlinePassed));
+        }
+
+        let liability = self.vesting.get_current_liability();
+
+        if withdraw_amount.amount > liability {
+            return Err(error!(err::arg(
+                "The amount of tokens to withdraw is bigger than\
+                the amount of vested tokens to be withdrawn"
+            )));
+        }
+
+        if withdraw_amount.amount > self.vesting.vault_balance.amount {
+            return Err(error!(err::arg(
+                "The amount of tokens to withdraw is higher \
+                than the amount of tokens currently in vault, \
+                it seems the vault is partially unfunded"
+            )));
+        }
+
+        self.vesting
+            .apply_rate_limit(clock_ts.time, withdraw_amount.amount)?;
+
+        if vestee_wallet.can_receive {
+            self.transfer_to_vestee_wallet(withdraw_amount, signer_bump_seed)?;
+        }
+
+        self.vesting
+            .record_withdrawal(withdraw_amount, vestee_wallet.can_receive);
+
+        if vestee_wallet.can_receive {
+            emit!(VestedTokensWithdrawn {
+                vesting: self.vesting.key(),
+                vestee_wallet: self.vestee_wallet.key(),
+                amount: withdraw_amount,
+            });
+        } else {
+            msg!("The vestee wallet cannot receive tokens, escrowing them");
+            emit!(VestedTokensEscrowed {
+                vesting: self.vesting.key(),
+                vestee_wallet: self.vestee_wallet.key(),
+                amount: withdraw_amount,
+                claimable_balance: self.vesting.claimable_balance,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Transfers tokens from the vesting vault to the vestee wallet.
+    pub fn transfer_to_vestee_wallet(
+        &self,
+        amount: TokenAmount,
+        signer_bump_seed: u8,
+    ) -> Result<()> {
+        let signer_seeds = &[
+            Vesting::SIGNER_PDA_PREFIX,
+            &self.vesting.key().to_bytes()[..],
+            &[signer_bump_seed],
+        ];
+
+        token::transfer(
+            self.as_transfer_funds_from_vesting_vault_to_vestee_wallet_context()
+                .with_signer(&[&signer_seeds[..]]),
+            amount.amount,
+        )
+    }
+
     fn as_transfer_funds_from_vesting_vault_to_vestee_wallet_context(
         &self,
     ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
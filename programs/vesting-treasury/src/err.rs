@@ -17,6 +17,28 @@ pub enum TreasuryError {
     /// program logic works as intended.
     #[msg("There's a bug in the program, see logs for more info")]
     InvariantViolation,
+    #[msg("Withdrawals are blocked during an active blackout window")]
+    WithdrawalInBlackoutWindow,
+    #[msg("The claim deadline of this vesting schedule has passed")]
+    ClaimDeadlinePassed,
+    #[msg("Withdrawal exceeds the rate limit of this vesting schedule")]
+    WithdrawalRateLimitExceeded,
+    #[msg("The vesting amount must be positive")]
+    ZeroVestingAmount,
+    #[msg("The total number of periods must be positive")]
+    ZeroTotalPeriods,
+    #[msg("The number of cliff periods cannot be higher than the total number of periods")]
+    CliffExceedsTotalPeriods,
+    #[msg("Only vesting schedules with daily or monthly periods are supported")]
+    UnsupportedPeriodType,
+    #[msg("The start of the vesting schedule is too far from the current time")]
+    StartTimestampOutOfBounds,
+    #[msg("The end of the vesting schedule is out of the supported date range")]
+    ScheduleEndOverflow,
+    #[msg("This admin action must be queued because the vesting account has a timelock")]
+    AdminActionTimelocked,
+    #[msg("This vesting account is frozen")]
+    VestingFrozen,
 }
 
 pub fn acc(msg: impl Display) -> TreasuryError {
@@ -0,0 +1,179 @@
+use crate::prelude::*;
+
+/// Emitted whenever vested tokens are transferred to the vestee wallet.
+#[event]
+pub struct VestedTokensWithdrawn {
+    pub vesting: Pubkey,
+    pub vestee_wallet: Pubkey,
+    pub amount: TokenAmount,
+}
+
+/// Emitted whenever the admin replaces the blackout windows of a vesting
+/// account. An empty list means that all windows were lifted.
+#[event]
+pub struct BlackoutWindowsSet {
+    pub vesting: Pubkey,
+    pub windows: Vec<BlackoutWindow>,
+}
+
+/// Emitted whenever the owner of the vestee wallet sets the withdrawal
+/// delegate, or removes it in which case `delegate` is the default pubkey.
+#[event]
+pub struct WithdrawalDelegateSet {
+    pub vesting: Pubkey,
+    pub delegate: Pubkey,
+}
+
+/// Emitted whenever a funder transfers tokens to the vault via
+/// `fund_vesting_vault`, which makes the contributions of each funder
+/// traceable.
+#[event]
+pub struct VestingVaultFunded {
+    pub vesting: Pubkey,
+    pub funder: Pubkey,
+    pub funding_wallet: Pubkey,
+    pub amount: TokenAmount,
+}
+
+/// Emitted whenever `sync_vault` records the actual balance of the vault,
+/// such that indexers can reconcile tokens sent straight to the vault.
+#[event]
+pub struct VaultSynced {
+    pub vesting: Pubkey,
+    pub previous_vault_balance: TokenAmount,
+    pub vault_balance: TokenAmount,
+}
+
+/// Emitted whenever surplus tokens are returned from the vault.
+#[event]
+pub struct SurplusRefunded {
+    pub vesting: Pubkey,
+    pub refund_wallet: Pubkey,
+    pub amount: TokenAmount,
+}
+
+/// Emitted whenever the unclaimed tokens are swept from the vault after the
+/// claim deadline.
+#[event]
+pub struct VestingSwept {
+    pub vesting: Pubkey,
+    pub recovery_wallet: Pubkey,
+    pub amount: TokenAmount,
+}
+
+/// Emitted whenever the admin nominates a new admin, or cancels the
+/// nomination in which case `pending_admin` is the default pubkey.
+#[event]
+pub struct AdminProposed {
+    pub vesting: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/// Emitted whenever the admin of a vesting account changes.
+#[event]
+pub struct AdminChanged {
+    pub vesting: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+/// Emitted whenever the admin changes the roles of a vesting account.
+#[event]
+pub struct RolesChanged {
+    pub vesting: Pubkey,
+    pub roles: VestingRoles,
+}
+
+/// Emitted whenever the timelock of a vesting account changes, be it set
+/// directly with `set_timelock` or through a queued admin action.
+#[event]
+pub struct TimelockChanged {
+    pub vesting: Pubkey,
+    pub action_delay_secs: i64,
+    pub guardian: Pubkey,
+}
+
+/// Emitted whenever an admin action is queued on a vesting account with a
+/// timelock.
+#[event]
+pub struct AdminActionQueued {
+    pub vesting: Pubkey,
+    pub queued_action: Pubkey,
+    pub action: AdminAction,
+    pub eta: TimeStamp,
+}
+
+/// Emitted whenever a queued admin action is cancelled.
+#[event]
+pub struct AdminActionCancelled {
+    pub vesting: Pubkey,
+    pub queued_action: Pubkey,
+    pub canceller: Pubkey,
+}
+
+/// Emitted whenever a queued admin action is executed.
+#[event]
+pub struct AdminActionExecuted {
+    pub vesting: Pubkey,
+    pub queued_action: Pubkey,
+    pub action: AdminAction,
+}
+
+/// Emitted whenever the owner of the vestee wallet requests to move to a new
+/// one, which awaits the approval of the wallet manager.
+#[event]
+pub struct VesteeWalletChangeRequested {
+    pub vesting: Pubkey,
+    pub vestee_wallet: Pubkey,
+    pub requested_vestee_wallet: Pubkey,
+}
+
+/// Emitted whenever the vestee wallet of a vesting account changes.
+#[event]
+pub struct VesteeWalletChanged {
+    pub vesting: Pubkey,
+    pub previous_vestee_wallet: Pubkey,
+    pub vestee_wallet: Pubkey,
+}
+
+/// Emitted whenever the owner of the vestee wallet adds, updates or removes
+/// an operator. Removed operators are reported without any permissions.
+#[event]
+pub struct VesteeOperatorSet {
+    pub vesting: Pubkey,
+    pub operator: VesteeOperator,
+}
+
+/// Emitted whenever withdrawn tokens could not be delivered to the vestee
+/// wallet and were added to the claimable balance instead.
+#[event]
+pub struct VestedTokensEscrowed {
+    pub vesting: Pubkey,
+    pub vestee_wallet: Pubkey,
+    pub amount: TokenAmount,
+    /// The claimable balance including this amount.
+    pub claimable_balance: TokenAmount,
+}
+
+/// Emitted whenever the claimable balance is pulled to the vestee wallet.
+#[event]
+pub struct ClaimableBalanceWithdrawn {
+    pub vesting: Pubkey,
+    pub vestee_wallet: Pubkey,
+    pub amount: TokenAmount,
+}
+
+/// Emitted whenever the admin or the guardian freezes a vesting account.
+#[event]
+pub struct VestingFrozen {
+    pub vesting: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Emitted whenever the admin or the guardian unfreezes a vesting account.
+#[event]
+pub struct VestingUnfrozen {
+    pub vesting: Pubkey,
+    pub authority: Pubkey,
+}
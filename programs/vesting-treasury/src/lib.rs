@@ -1,5 +1,6 @@
 pub mod endpoints;
 pub mod err;
+pub mod events;
 pub mod models;
 pub mod periods;
 pub mod prelude;
@@ -14,13 +15,14 @@ declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 pub mod vesting_treasury {
     use super::*;
 
-    pub fn create_vesting_schedule(
-        ctx: Context<CreateVestingSchedule>,
+    pub fn create_vesting_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateVestingSchedule<'info>>,
         vesting_amount: TokenAmount,
         start_ts: TimeStamp,
         cliff_periods: u64,
         total_periods: u64,
         period_type: u32,
+        options: VestingOptions,
     ) -> Result<()> {
         endpoints::create_vesting_schedule::handle(
             ctx,
@@ -29,13 +31,189 @@ pub mod vesting_treasury {
             cliff_periods,
             total_periods,
             period_type,
+            options,
         )
     }
 
-    pub fn change_vestee_wallet(ctx: Context<ChangeVesteeWallet>) -> Result<()> {
+    pub fn create_vesting_schedule_v2<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateVestingSchedule<'info>>,
+        params: ScheduleParams,
+        options: VestingOptions,
+    ) -> Result<()> {
+        endpoints::create_vesting_schedule_v2::handle(ctx, params, options)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_and_fund_vesting_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateAndFundVestingSchedule<'info>>,
+        vesting_amount: TokenAmount,
+        start_ts: TimeStamp,
+        cliff_periods: u64,
+        total_periods: u64,
+        period_type: u32,
+        options: VestingOptions,
+        funding_amount: TokenAmount,
+    ) -> Result<()> {
+        endpoints::create_and_fund_vesting_schedule::handle(
+            ctx,
+            vesting_amount,
+            start_ts,
+            cliff_periods,
+            total_periods,
+            period_type,
+            options,
+            funding_amount,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_deterministic_vesting_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateDeterministicVestingSchedule<'info>>,
+        grant_id: u64,
+        vesting_amount: TokenAmount,
+        start_ts: TimeStamp,
+        cliff_periods: u64,
+        total_periods: u64,
+        period_type: u32,
+        options: VestingOptions,
+    ) -> Result<()> {
+        endpoints::create_deterministic_vesting_schedule::handle(
+            ctx,
+            grant_id,
+            vesting_amount,
+            start_ts,
+            cliff_periods,
+            total_periods,
+            period_type,
+            options,
+        )
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, pending_admin: Pubkey) -> Result<()> {
+        endpoints::propose_admin::handle(ctx, pending_admin)
+    }
+
+    pub fn accept_admin<'info>(ctx: Context<'_, '_, '_, 'info, AcceptAdmin<'info>>) -> Result<()> {
+        endpoints::accept_admin::handle(ctx)
+    }
+
+    pub fn rotate_admin_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, RotateAdminBatch<'info>>,
+    ) -> Result<()> {
+        endpoints::rotate_admin_batch::handle(ctx)
+    }
+
+    pub fn set_roles(ctx: Context<SetRoles>, roles: VestingRoles) -> Result<()> {
+        endpoints::set_roles::handle(ctx, roles)
+    }
+
+    pub fn set_timelock(
+        ctx: Context<SetTimelock>,
+        action_delay_secs: i64,
+        guardian: Pubkey,
+    ) -> Result<()> {
+        endpoints::set_timelock::handle(ctx, action_delay_secs, guardian)
+    }
+
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        endpoints::create_multisig::handle(ctx, members, threshold)
+    }
+
+    pub fn change_multisig(
+        ctx: Context<ChangeMultisig>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        endpoints::change_multisig::handle(ctx, members, threshold)
+    }
+
+    pub fn propose_multisig_action(
+        ctx: Context<ProposeMultisigAction>,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        endpoints::propose_multisig_action::handle(ctx, accounts, data)
+    }
+
+    pub fn approve_multisig_action(ctx: Context<ApproveMultisigAction>) -> Result<()> {
+        endpoints::approve_multisig_action::handle(ctx)
+    }
+
+    pub fn execute_multisig_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteMultisigAction<'info>>,
+    ) -> Result<()> {
+        endpoints::execute_multisig_action::handle(ctx)
+    }
+
+    pub fn cancel_multisig_action(ctx: Context<CancelMultisigAction>) -> Result<()> {
+        endpoints::cancel_multisig_action::handle(ctx)
+    }
+
+    pub fn invoke_multisig_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, InvokeMultisigAction<'info>>,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        endpoints::invoke_multisig_action::handle(ctx, accounts, data)
+    }
+
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        endpoints::queue_admin_action::handle(ctx, action)
+    }
+
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+        endpoints::cancel_admin_action::handle(ctx)
+    }
+
+    pub fn execute_admin_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAdminAction<'info>>,
+    ) -> Result<()> {
+        endpoints::execute_admin_action::handle(ctx)
+    }
+
+    pub fn refund_surplus(ctx: Context<RefundSurplus>, refund_amount: TokenAmount) -> Result<()> {
+        endpoints::refund_surplus::handle(ctx, refund_amount)
+    }
+
+    pub fn sync_vault(ctx: Context<SyncVault>) -> Result<()> {
+        endpoints::sync_vault::handle(ctx)
+    }
+
+    pub fn migrate_vesting(ctx: Context<MigrateVesting>) -> Result<()> {
+        endpoints::migrate_vesting::handle(ctx)
+    }
+
+    pub fn create_registry_page(
+        ctx: Context<CreateRegistryPage>,
+        kind: RegistryKind,
+        owner: Pubkey,
+        page: u32,
+    ) -> Result<()> {
+        endpoints::create_registry_page::handle(ctx, kind, owner, page)
+    }
+
+    pub fn change_vestee_wallet<'info>(
+        ctx: Context<'_, '_, '_, 'info, ChangeVesteeWallet<'info>>,
+    ) -> Result<()> {
         endpoints::change_vestee_wallet::handle(ctx)
     }
 
+    pub fn request_vestee_wallet_change<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequestVesteeWalletChange<'info>>,
+    ) -> Result<()> {
+        endpoints::request_vestee_wallet_change::handle(ctx)
+    }
+
+    pub fn approve_vestee_wallet_change<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveVesteeWalletChange<'info>>,
+    ) -> Result<()> {
+        endpoints::approve_vestee_wallet_change::handle(ctx)
+    }
+
     pub fn update_vested_tokens(ctx: Context<UpdateVestedTokens>) -> Result<()> {
         endpoints::update_vested_tokens::handle(ctx)
     }
@@ -47,14 +225,61 @@ pub mod vesting_treasury {
         endpoints::fund_vesting_vault::handle(ctx, funding_amount)
     }
 
-    pub fn withdraw_vested_tokens(
-        ctx: Context<WithdrawVestedTokens>,
+    pub fn withdraw_vested_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawVestedTokens<'info>>,
         withdraw_amount: TokenAmount,
     ) -> Result<()> {
         endpoints::withdraw_vested_tokens::handle(ctx, withdraw_amount)
     }
 
-    pub fn close_vesting_schedule(ctx: Context<CloseVestingSchedule>) -> Result<()> {
+    pub fn withdraw_max_vested_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawVestedTokens<'info>>,
+    ) -> Result<()> {
+        endpoints::withdraw_max_vested_tokens::handle(ctx)
+    }
+
+    pub fn withdraw_claimable_balance<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawVestedTokens<'info>>,
+    ) -> Result<()> {
+        endpoints::withdraw_claimable_balance::handle(ctx)
+    }
+
+    pub fn close_vesting_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseVestingSchedule<'info>>,
+    ) -> Result<()> {
         endpoints::close_vesting_schedule::handle(ctx)
     }
+
+    pub fn set_blackout_windows(
+        ctx: Context<SetBlackoutWindows>,
+        windows: Vec<BlackoutWindow>,
+    ) -> Result<()> {
+        endpoints::set_blackout_windows::handle(ctx, windows)
+    }
+
+    pub fn set_withdrawal_delegate(
+        ctx: Context<SetWithdrawalDelegate>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        endpoints::set_withdrawal_delegate::handle(ctx, delegate)
+    }
+
+    pub fn set_vestee_operator(
+        ctx: Context<SetVesteeOperator>,
+        operator: VesteeOperator,
+    ) -> Result<()> {
+        endpoints::set_vestee_operator::handle(ctx, operator)
+    }
+
+    pub fn freeze_vesting(ctx: Context<FreezeVesting>) -> Result<()> {
+        endpoints::freeze_vesting::handle(ctx)
+    }
+
+    pub fn unfreeze_vesting(ctx: Context<UnfreezeVesting>) -> Result<()> {
+        endpoints::unfreeze_vesting::handle(ctx)
+    }
+
+    pub fn sweep_unclaimed_tokens(ctx: Context<SweepUnclaimedTokens>) -> Result<()> {
+        endpoints::sweep_unclaimed_tokens::handle(ctx)
+    }
 }
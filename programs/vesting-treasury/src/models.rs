@@ -1,6 +1,12 @@
+pub mod multisig;
+pub mod registry;
+pub mod timelock;
 pub mod vesting;
 
 use crate::prelude::*;
+pub use multisig::*;
+pub use registry::*;
+pub use timelock::*;
 pub use vesting::*;
 
 #[derive(
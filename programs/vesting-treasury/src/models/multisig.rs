@@ -0,0 +1,318 @@
+use crate::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+
+/// How many members a [`Multisig`] can have. A free constant rather than an
+/// associated one, such that the IDL can resolve it in the array lengths of
+/// the account fields.
+pub const MAX_MEMBERS: usize = 10;
+
+/// A set of keys which act together as the admin, or as any role, of
+/// [`Vesting`] accounts. The set acts through its multisig signer, the PDA
+/// derived from [`Multisig::SIGNER_PDA_PREFIX`] and the address of the set,
+/// which is used in place of a single admin key. The multisig signer signs an
+/// instruction of this program once `threshold` members approve it, either
+/// by signing the same transaction or by approving a [`MultisigProposal`]
+/// over time. Hence the threshold applies to every admin-gated endpoint.
+#[derive(Default, Debug)]
+#[account]
+pub struct Multisig {
+    /// The members of the set. Only the first `member_count` slots are used,
+    /// the rest is left with the default pubkey.
+    pub members: [Pubkey; MAX_MEMBERS],
+    pub member_count: u8,
+    /// How many distinct members must approve an instruction.
+    pub threshold: u8,
+    /// Incremented whenever the members or the threshold change, which
+    /// invalidates the approvals of pending proposals.
+    pub seqno: u32,
+}
+
+impl Multisig {
+    pub const SIGNER_PDA_PREFIX: &'static [u8; 8] = b"multisig";
+
+    pub fn space() -> usize {
+        let discriminant = 8;
+
+        // All fields are of fixed size
+        let fields = Self::default()
+            .try_to_vec()
+            .expect("Multisig always serializes into a vec")
+            .len();
+
+        discriminant + fields
+    }
+
+    pub fn members(&self) -> &[Pubkey] {
+        &self.members[..self.member_count as usize]
+    }
+
+    /// Replaces the members and the threshold of the set. The members must be
+    /// distinct, and the threshold can be at most the number of members.
+    pub fn set_members(&mut self, members: &[Pubkey], threshold: u8) -> Result<()> {
+        if members.is_empty() || members.len() > MAX_MEMBERS {
+            return Err(error!(err::arg(format!(
+                "A multisig must have between 1 and {} members",
+                MAX_MEMBERS
+            ))));
+        }
+
+        for (index, member) in members.iter().enumerate() {
+            if *member == Pubkey::default() || members[..index].contains(member) {
+                return Err(error!(err::arg(
+                    "Multisig members must be distinct and not the default pubkey"
+                )));
+            }
+        }
+
+        if threshold == 0 || threshold as usize > members.len() {
+            return Err(error!(err::arg(
+                "The threshold must be positive and at most the number of members"
+            )));
+        }
+
+        self.members = Default::default();
+        self.members[..members.len()].copy_from_slice(members);
+        self.member_count = members.len() as u8;
+        self.threshold = threshold;
+        self.seqno = self.seqno.wrapping_add(1);
+
+        Ok(())
+    }
+
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members().iter().position(|member| member == key)
+    }
+
+    /// Whether the given keys, which must have signed, include at least
+    /// `threshold` distinct members. Keys which are not members are ignored.
+    pub fn is_approved_by<'a>(&self, signers: impl IntoIterator<Item = &'a Pubkey>) -> bool {
+        let mut approvals = [false; MAX_MEMBERS];
+        for signer in signers {
+            if let Some(index) = self.member_index(signer) {
+                approvals[index] = true;
+            }
+        }
+
+        self.has_threshold(&approvals)
+    }
+
+    fn has_threshold(&self, approvals: &[bool; MAX_MEMBERS]) -> bool {
+        let count = approvals.iter().filter(|approved| **approved).count();
+
+        count >= self.threshold as usize
+    }
+}
+
+/// An instruction of this program proposed to be signed by the multisig
+/// signer of a [`Multisig`], which the members approve one by one. Once
+/// enough members approved, anyone can execute it.
+#[derive(Default, Debug)]
+#[account]
+pub struct MultisigProposal {
+    /// The set whose members approve the proposal.
+    pub multisig: Pubkey,
+    /// Paid the rent of the proposal, which is returned to it once the
+    /// proposal is executed or cancelled.
+    pub payer: Pubkey,
+    /// The [`Multisig::seqno`] at the time of the proposal. If the members
+    /// change, the proposal can no longer be approved nor executed.
+    pub seqno: u32,
+    /// The accounts of the proposed instruction.
+    pub accounts: Vec<ProposalAccount>,
+    /// The data of the proposed instruction.
+    pub data: Vec<u8>,
+    /// Which members approved, by their index in [`Multisig::members`].
+    pub approvals: [bool; MAX_MEMBERS],
+    /// Set right before the instruction is invoked, such that it cannot be
+    /// executed again from within itself.
+    pub executed: bool,
+}
+
+impl MultisigProposal {
+    pub const MAX_ACCOUNTS: usize = 20;
+    pub const MAX_DATA_LEN: usize = 512;
+
+    pub fn space() -> usize {
+        let discriminant = 8;
+        let multisig = 32;
+        let payer = 32;
+        let seqno = 4;
+        let accounts = 4 + ProposalAccount::SPACE * Self::MAX_ACCOUNTS;
+        let data = 4 + Self::MAX_DATA_LEN;
+        let approvals = MAX_MEMBERS;
+        let executed = 1;
+
+        discriminant + multisig + payer + seqno + accounts + data + approvals + executed
+    }
+
+    /// Records the approval of a member. Fails if the members of the set
+    /// changed since the proposal was made.
+    pub fn approve(&mut self, multisig: &Multisig, member: &Pubkey) -> Result<()> {
+        self.check_seqno(multisig)?;
+
+        let index = multisig
+            .member_index(member)
+            .ok_or_else(|| error!(err::acc("Signer is not a member of the multisig")))?;
+        self.approvals[index] = true;
+
+        Ok(())
+    }
+
+    pub fn is_approved(&self, multisig: &Multisig) -> Result<bool> {
+        self.check_seqno(multisig)?;
+
+        Ok(multisig.has_threshold(&self.approvals))
+    }
+
+    fn check_seqno(&self, multisig: &Multisig) -> Result<()> {
+        if self.seqno != multisig.seqno {
+            return Err(error!(err::acc(
+                "The members of the multisig changed since the proposal was made"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// An account of an instruction to be signed by a multisig signer, see
+/// [`AccountMeta`].
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl ProposalAccount {
+    pub const SPACE: usize = 32 + 1 + 1;
+}
+
+/// Builds an instruction of this program in which the multisig signer signs.
+/// Only this program can be called, hence the multisig cannot be used to
+/// sign for anything else than the endpoints of the vesting treasury.
+pub fn multisig_instruction(
+    multisig_signer: &Pubkey,
+    accounts: &[ProposalAccount],
+    data: &[u8],
+) -> Instruction {
+    let accounts = accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer || account.pubkey == *multisig_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+
+    Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: data.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_members() {
+        let mut multisig = Multisig::default();
+        let member = Pubkey::new_unique();
+
+        assert!(multisig.set_members(&[], 1).is_err());
+        assert!(multisig.set_members(&[member, member], 1).is_err());
+        assert!(multisig.set_members(&[Pubkey::default()], 1).is_err());
+        assert!(multisig.set_members(&[member], 0).is_err());
+        assert!(multisig.set_members(&[member], 2).is_err());
+        assert!(multisig
+            .set_members(&[Pubkey::new_unique(); MAX_MEMBERS + 1], 1)
+            .is_err());
+        assert_eq!(multisig.seqno, 0);
+    }
+
+    #[test]
+    fn it_counts_distinct_member_signers() -> Result<()> {
+        let mut multisig = Multisig::default();
+        let members = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        multisig.set_members(&members, 2)?;
+        assert_eq!(multisig.members(), &members);
+
+        let outsider = Pubkey::new_unique();
+        assert!(!multisig.is_approved_by(&[members[0], members[0], outsider]));
+        assert!(multisig.is_approved_by(&[members[0], outsider, members[2]]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_invalidates_approvals_when_members_change() -> Result<()> {
+        let mut multisig = Multisig::default();
+        let members = [Pubkey::new_unique(), Pubkey::new_unique()];
+        multisig.set_members(&members, 2)?;
+
+        let mut proposal = MultisigProposal {
+            seqno: multisig.seqno,
+            ..Default::default()
+        };
+        assert!(proposal.approve(&multisig, &Pubkey::new_unique()).is_err());
+
+        proposal.approve(&multisig, &members[0])?;
+        proposal.approve(&multisig, &members[0])?;
+        assert!(!proposal.is_approved(&multisig)?);
+
+        proposal.approve(&multisig, &members[1])?;
+        assert!(proposal.is_approved(&multisig)?);
+
+        multisig.set_members(&members, 1)?;
+        assert!(proposal.is_approved(&multisig).is_err());
+        assert!(proposal.approve(&multisig, &members[0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fits_largest_proposal_into_space() {
+        let proposal = MultisigProposal {
+            accounts: vec![ProposalAccount::default(); MultisigProposal::MAX_ACCOUNTS],
+            data: vec![0; MultisigProposal::MAX_DATA_LEN],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            proposal.try_to_vec().unwrap().len() + 8,
+            MultisigProposal::space()
+        );
+    }
+
+    #[test]
+    fn it_signs_with_multisig_signer() {
+        let multisig_signer = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let accounts = [
+            ProposalAccount {
+                pubkey: multisig_signer,
+                is_signer: false,
+                is_writable: false,
+            },
+            ProposalAccount {
+                pubkey: other,
+                is_signer: false,
+                is_writable: true,
+            },
+        ];
+
+        let ix = multisig_instruction(&multisig_signer, &accounts, &[1, 2]);
+
+        assert_eq!(ix.program_id, crate::ID);
+        assert!(ix.accounts[0].is_signer);
+        assert!(!ix.accounts[1].is_signer);
+        assert!(ix.accounts[1].is_writable);
+        assert_eq!(ix.data, vec![1, 2]);
+    }
+}
@@ -0,0 +1,357 @@
+use crate::prelude::*;
+use std::mem;
+
+/// A page of the index of [`Vesting`] accounts that belong to an admin or to
+/// a vestee. It allows wallets and other programs to enumerate the vesting
+/// schedules of a key without scanning all program accounts. The pages are
+/// PDAs derived from [`VestingRegistry::PREFIX`], the kind, the owner and the
+/// page number, and they are kept up to date by the endpoints which create
+/// and close vesting schedules when passed to them, see [`RegistryPages`].
+#[derive(Default, Debug)]
+#[account]
+pub struct VestingRegistry {
+    /// Whether this page indexes the vesting accounts of an admin or of a
+    /// vestee.
+    pub kind: RegistryKind,
+    /// The admin, or the owner of the vestee wallet, whose vesting accounts
+    /// are indexed.
+    pub owner: Pubkey,
+    /// Number of this page amongst all the pages of the owner.
+    pub page: u32,
+    /// The vesting accounts listed in this page.
+    pub schedules: Vec<Pubkey>,
+}
+
+impl VestingRegistry {
+    pub const PREFIX: &'static [u8; 8] = b"registry";
+    pub const MAX_SCHEDULES_PER_PAGE: usize = 32;
+
+    pub fn space() -> usize {
+        let discriminant = 8;
+        let kind = mem::size_of::<RegistryKind>();
+        let owner = 32;
+        let page = mem::size_of::<u32>();
+        let schedules = mem::size_of::<u32>() + 32 * Self::MAX_SCHEDULES_PER_PAGE;
+
+        discriminant + kind + owner + page + schedules
+    }
+
+    /// Lists a vesting account in this page, failing if the page is full in
+    /// which case the next page should be used.
+    pub fn insert(&mut self, vesting: Pubkey) -> Result<()> {
+        if self.schedules.len() >= Self::MAX_SCHEDULES_PER_PAGE {
+            return Err(error!(err::acc(format!(
+                "Registry page {} is full, use the next page",
+                self.page
+            ))));
+        }
+
+        if self.schedules.contains(&vesting) {
+            return Err(error!(TreasuryError::InvariantViolation));
+        }
+
+        self.schedules.push(vesting);
+
+        Ok(())
+    }
+
+    /// Removes a vesting account from this page, failing if it is not listed
+    /// in it.
+    pub fn remove(&mut self, vesting: &Pubkey) -> Result<()> {
+        let index = self
+            .schedules
+            .iter()
+            .position(|listed| listed == vesting)
+            .ok_or_else(|| {
+                error!(err::acc(
+                    "Vesting account is not listed in the provided registry page"
+                ))
+            })?;
+
+        self.schedules.swap_remove(index);
+
+        Ok(())
+    }
+
+    /// Moves a vesting account from this page to the given page of the
+    /// registry of the same kind, whose owner took over the vesting account.
+    pub fn move_schedule(&mut self, to: &mut VestingRegistry, vesting: Pubkey) -> Result<()> {
+        if self.kind != to.kind {
+            return Err(error!(err::acc(
+                "Registry pages of different kinds cannot exchange vesting accounts"
+            )));
+        }
+
+        self.remove(&vesting)?;
+        to.insert(vesting)
+    }
+}
+
+/// The page of the admin registry and the page of the vestee registry in
+/// which a vesting account is listed. The endpoints which create or close
+/// vesting schedules take them as optional remaining accounts, the admin page
+/// first.
+pub struct RegistryPages<'info> {
+    pub admin: Account<'info, VestingRegistry>,
+    pub vestee: Account<'info, VestingRegistry>,
+}
+
+impl<'info> RegistryPages<'info> {
+    /// Loads the pages from the remaining accounts of an instruction, or
+    /// returns `None` if no remaining accounts were passed.
+    pub fn load(remaining_accounts: &[AccountInfo<'info>]) -> Result<Option<Self>> {
+        let (admin_info, vestee_info) = match remaining_accounts {
+            [] => return Ok(None),
+            [admin_info, vestee_info] => (admin_info, vestee_info),
+            _ => {
+                return Err(error!(err::acc(
+                    "Registry pages must be passed as the admin page followed by the vestee page"
+                )))
+            }
+        };
+
+        if !admin_info.is_writable || !vestee_info.is_writable {
+            return Err(error!(err::acc("Registry pages must be writable")));
+        }
+
+        let admin = Account::<VestingRegistry>::try_from(admin_info)?;
+        if admin.kind != RegistryKind::Admin {
+            return Err(error!(err::acc(
+                "Admin registry must be a page of an admin's registry"
+            )));
+        }
+
+        let vestee = Account::<VestingRegistry>::try_from(vestee_info)?;
+        if vestee.kind != RegistryKind::Vestee {
+            return Err(error!(err::acc(
+                "Vestee registry must be a page of a vestee's registry"
+            )));
+        }
+
+        Ok(Some(Self { admin, vestee }))
+    }
+
+    /// Lists a new vesting account in the registry of its admin and in the
+    /// one of the owner of its vestee wallet.
+    pub fn insert(
+        mut self,
+        vesting: Pubkey,
+        admin: Pubkey,
+        vestee_wallet_owner: Pubkey,
+    ) -> Result<()> {
+        if self.admin.owner != admin {
+            return Err(error!(err::acc(
+                "Admin registry must be a page of the admin's registry"
+            )));
+        }
+        if self.vestee.owner != vestee_wallet_owner {
+            return Err(error!(err::acc(
+                "Vestee registry must be a page of the vestee wallet owner's registry"
+            )));
+        }
+
+        self.admin.insert(vesting)?;
+        self.vestee.insert(vesting)?;
+
+        self.admin.exit(&crate::ID)?;
+        self.vestee.exit(&crate::ID)
+    }
+
+    /// Removes a closed vesting account from the pages which list it, which
+    /// are those of its current admin and vestee, see [`RegistryMove`].
+    pub fn remove(mut self, vesting: &Pubkey) -> Result<()> {
+        self.admin.remove(vesting)?;
+        self.vestee.remove(vesting)?;
+
+        self.admin.exit(&crate::ID)?;
+        self.vestee.exit(&crate::ID)
+    }
+}
+
+/// The page which lists a registered vesting account and a page of the
+/// registry of the key which takes over as its admin or as its vestee. The
+/// endpoints which change the admin or the owner of the vestee wallet take
+/// them as remaining accounts, the listing page first, such that the
+/// registries keep listing each vesting account under its current admin and
+/// vestee.
+pub struct RegistryMove<'info> {
+    pub from: Account<'info, VestingRegistry>,
+    pub to: Account<'info, VestingRegistry>,
+}
+
+impl<'info> RegistryMove<'info> {
+    /// Moves the listing of the vesting account from the page of the previous
+    /// admin to a page of its current admin. Returns the remaining accounts
+    /// which follow the pages, or all of them if the vesting account is not
+    /// registered or the admin did not change.
+    pub fn follow_admin<'a>(
+        vesting: &Account<'info, Vesting>,
+        previous_admin: Pubkey,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<&'a [AccountInfo<'info>]> {
+        Self::follow(
+            vesting,
+            RegistryKind::Admin,
+            previous_admin,
+            vesting.admin,
+            remaining_accounts,
+        )
+    }
+
+    /// Moves the listing of the vesting account from the page of the previous
+    /// owner of the vestee wallet to a page of the current one, like
+    /// [`RegistryMove::follow_admin`].
+    pub fn follow_vestee<'a>(
+        vesting: &Account<'info, Vesting>,
+        previous_vestee: Pubkey,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<&'a [AccountInfo<'info>]> {
+        Self::follow(
+            vesting,
+            RegistryKind::Vestee,
+            previous_vestee,
+            vesting.vestee_wallet_owner,
+            remaining_accounts,
+        )
+    }
+
+    fn follow<'a>(
+        vesting: &Account<'info, Vesting>,
+        kind: RegistryKind,
+        previous_owner: Pubkey,
+        owner: Pubkey,
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<&'a [AccountInfo<'info>]> {
+        if !vesting.registered || previous_owner == owner {
+            return Ok(remaining_accounts);
+        }
+
+        let (from_info, to_info, rest) = match remaining_accounts {
+            [from_info, to_info, rest @ ..] => (from_info, to_info, rest),
+            _ => {
+                return Err(error!(err::acc(
+                    "The registry page which lists the vesting account and a page of \
+                    its new owner must be passed as remaining accounts"
+                )))
+            }
+        };
+
+        if !from_info.is_writable || !to_info.is_writable {
+            return Err(error!(err::acc("Registry pages must be writable")));
+        }
+
+        let mut from = Account::<VestingRegistry>::try_from(from_info)?;
+        let mut to = Account::<VestingRegistry>::try_from(to_info)?;
+        if from.kind != kind || to.owner != owner {
+            return Err(error!(err::acc(
+                "Registry page must be a page of the new owner's registry"
+            )));
+        }
+
+        from.move_schedule(&mut to, vesting.key())?;
+
+        from.exit(&crate::ID)?;
+        to.exit(&crate::ID)?;
+
+        Ok(rest)
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RegistryKind {
+    Admin,
+    Vestee,
+}
+
+impl Default for RegistryKind {
+    fn default() -> Self {
+        RegistryKind::Admin
+    }
+}
+
+impl RegistryKind {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            RegistryKind::Admin => b"admin",
+            RegistryKind::Vestee => b"vestee",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_inserts_and_removes_schedules() -> Result<()> {
+        let mut registry = VestingRegistry::default();
+        let vesting1 = Pubkey::new_unique();
+        let vesting2 = Pubkey::new_unique();
+        let vesting3 = Pubkey::new_unique();
+
+        registry.insert(vesting1)?;
+        registry.insert(vesting2)?;
+        registry.insert(vesting3)?;
+        assert_eq!(registry.schedules, vec![vesting1, vesting2, vesting3]);
+
+        registry.remove(&vesting1)?;
+        assert_eq!(registry.schedules, vec![vesting3, vesting2]);
+
+        assert!(registry.remove(&vesting1).is_err());
+        assert!(registry.insert(vesting2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_moves_schedules_between_pages_of_a_kind() -> Result<()> {
+        let vesting = Pubkey::new_unique();
+        let mut from = VestingRegistry {
+            kind: RegistryKind::Vestee,
+            ..Default::default()
+        };
+        let mut to = VestingRegistry {
+            kind: RegistryKind::Vestee,
+            ..Default::default()
+        };
+        let mut admin = VestingRegistry::default();
+        from.insert(vesting)?;
+
+        assert!(from.move_schedule(&mut admin, vesting).is_err());
+
+        from.move_schedule(&mut to, vesting)?;
+        assert!(from.schedules.is_empty());
+        assert_eq!(to.schedules, vec![vesting]);
+
+        // Not listed anymore
+        assert!(from.move_schedule(&mut to, vesting).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_to_insert_into_full_page() -> Result<()> {
+        let mut registry = VestingRegistry::default();
+
+        for _ in 0..VestingRegistry::MAX_SCHEDULES_PER_PAGE {
+            registry.insert(Pubkey::new_unique())?;
+        }
+
+        assert!(registry.insert(Pubkey::new_unique()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fits_full_page_into_space() {
+        let registry = VestingRegistry {
+            schedules: vec![Pubkey::new_unique(); VestingRegistry::MAX_SCHEDULES_PER_PAGE],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            registry.try_to_vec().unwrap().len() + 8,
+            VestingRegistry::space()
+        );
+    }
+}
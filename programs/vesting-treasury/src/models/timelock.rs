@@ -0,0 +1,163 @@
+use crate::prelude::*;
+
+/// An admin action on a [`Vesting`] account which has a timelock, see
+/// [`Vesting::action_delay_secs`]. The action is queued by the key holding
+/// the corresponding power and takes effect only once it is executed after
+/// its `eta`, provided that key still holds the power. Until then the vestee,
+/// the guardian or the admin can cancel it.
+#[derive(Default, Debug)]
+#[account]
+pub struct QueuedAdminAction {
+    /// The vesting account the action applies to.
+    pub vesting: Pubkey,
+    /// The key which queued the action, see [`AdminAction::authority`].
+    pub authority: Pubkey,
+    /// Paid the rent of this account, which is returned to it once the
+    /// action is executed or cancelled.
+    pub payer: Pubkey,
+    /// The action can be executed at or after this time.
+    pub eta: TimeStamp,
+    pub action: AdminAction,
+}
+
+impl QueuedAdminAction {
+    pub fn space() -> usize {
+        let discriminant = 8;
+        let vesting = 32;
+        let authority = 32;
+        let payer = 32;
+        let eta = 8;
+        // The largest variant holds the four keys of the roles
+        let action = 1 + 4 * 32;
+
+        discriminant + vesting + authority + payer + eta + action
+    }
+
+    pub fn is_due(&self, clock_ts: i64) -> bool {
+        clock_ts >= self.eta.time
+    }
+
+    /// Fails unless the key which queued the action still holds its power,
+    /// such that changing the admin or a role drops the actions queued by the
+    /// previous key.
+    pub fn check_authority(&self, vesting: &Vesting) -> Result<()> {
+        if self.action.authority(vesting) != self.authority {
+            return Err(error!(err::acc(
+                "The key which queued the action no longer holds its power"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The admin actions which are subject to the timelock of a [`Vesting`]
+/// account.
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Points the vesting account to a new vestee wallet, as the endpoint
+    /// `change_vestee_wallet` does. Queued by the wallet manager.
+    ChangeVesteeWallet { vestee_wallet: Pubkey },
+    /// Nominates a new admin, as the endpoint `propose_admin` does. The
+    /// nominee must still accept. Queued by the admin.
+    ProposeAdmin { pending_admin: Pubkey },
+    /// Sweeps the unclaimed tokens after the claim deadline, as the endpoint
+    /// `sweep_unclaimed_tokens` does. Queued by the revoker.
+    SweepUnclaimedTokens { recovery_wallet: Pubkey },
+    /// Changes the timelock itself. Queued by the admin. A vesting account
+    /// without a timelock gets one with the endpoint `set_timelock`.
+    SetTimelock {
+        action_delay_secs: i64,
+        guardian: Pubkey,
+    },
+    /// Replaces the roles, as the endpoint `set_roles` does. Queued by the
+    /// admin.
+    SetRoles { roles: VestingRoles },
+}
+
+impl Default for AdminAction {
+    fn default() -> Self {
+        AdminAction::ProposeAdmin {
+            pending_admin: Pubkey::default(),
+        }
+    }
+}
+
+impl AdminAction {
+    /// The key which is allowed to queue the action.
+    pub fn authority(&self, vesting: &Vesting) -> Pubkey {
+        match self {
+            AdminAction::ChangeVesteeWallet { .. } => vesting.wallet_manager(),
+            AdminAction::ProposeAdmin { .. } => vesting.admin,
+            AdminAction::SweepUnclaimedTokens { .. } => vesting.revoker(),
+            AdminAction::SetTimelock { .. } => vesting.admin,
+            AdminAction::SetRoles { .. } => vesting.admin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_fits_largest_action_into_space() {
+        let queued = QueuedAdminAction {
+            authority: Pubkey::new_unique(),
+            action: AdminAction::SetRoles {
+                roles: VestingRoles {
+                    funder: Pubkey::new_unique(),
+                    wallet_manager: Pubkey::new_unique(),
+                    revoker: Pubkey::new_unique(),
+                    closer: Pubkey::new_unique(),
+                },
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            queued.try_to_vec().unwrap().len() + 8,
+            QueuedAdminAction::space()
+        );
+    }
+
+    #[test]
+    fn it_requires_role_to_queue() {
+        let admin = Pubkey::new_unique();
+        let wallet_manager = Pubkey::new_unique();
+        let vesting = Vesting {
+            admin,
+            roles: VestingRoles {
+                wallet_manager,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let change_wallet = AdminAction::ChangeVesteeWallet {
+            vestee_wallet: Pubkey::new_unique(),
+        };
+        assert_eq!(change_wallet.authority(&vesting), wallet_manager);
+        assert_eq!(AdminAction::default().authority(&vesting), admin);
+    }
+
+    #[test]
+    fn it_requires_role_to_execute() {
+        let admin = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            admin,
+            ..Default::default()
+        };
+        let queued = QueuedAdminAction {
+            authority: admin,
+            action: AdminAction::ChangeVesteeWallet {
+                vestee_wallet: Pubkey::new_unique(),
+            },
+            ..Default::default()
+        };
+        assert!(queued.check_authority(&vesting).is_ok());
+
+        vesting.roles.wallet_manager = Pubkey::new_unique();
+        assert!(queued.check_authority(&vesting).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
 use chrono::Duration;
-use std::mem;
 
 use crate::periods::monthly;
 
@@ -44,46 +45,256 @@ pub struct Vesting {
     /// schedule. This is required for computing vesting schedules depending
     /// on different base periods
     pub period_type: PeriodType,
+    /// Windows of time during which withdrawals are blocked, for example
+    /// around announcements that restrict insiders from trading. Vesting
+    /// keeps accruing during a window, only the transfers are blocked. Unused
+    /// slots are left with their default value.
+    pub blackout_windows: [BlackoutWindow; 8],
+    /// After this time the vestee can no longer withdraw and the admin can
+    /// sweep the tokens remaining in the vault. Zero means that the vested
+    /// tokens can be claimed indefinitely.
+    pub claim_deadline: TimeStamp,
+    /// If true, withdrawals must be signed by the owner of the vestee wallet
+    /// or by the withdrawal delegate. Otherwise anyone can trigger them.
+    pub beneficiary_only: bool,
+    /// A key registered by the owner of the vestee wallet which is allowed to
+    /// sign withdrawals on their behalf. The default pubkey means none.
+    pub withdrawal_delegate: Pubkey,
+    /// Optional cap on how many tokens can be withdrawn within a window of
+    /// time, on top of the vesting schedule itself.
+    pub rate_limit: RateLimit,
+    /// Index of the newest bucket of the rate limit, counted in
+    /// [`RateLimit::bucket_secs`] since the Unix epoch.
+    pub rate_limit_head_bucket: i64,
+    /// Amounts withdrawn within the most recent buckets of the rate limit,
+    /// such that the limit applies to a window rolling with the clock. The
+    /// bucket with index `i` is kept in the slot `i` modulo the number of
+    /// slots, see [`Vesting::apply_rate_limit`].
+    pub rate_limit_buckets: [TokenAmount; 9],
+    /// Layout version of this account, see [`Vesting::CURRENT_VERSION`].
+    /// Accounts created before versioning was introduced read as zero.
+    pub version: u8,
+    /// The key nominated by the admin to take over the admin role, which
+    /// becomes the admin once it accepts. The default pubkey means none.
+    pub pending_admin: Pubkey,
+    /// Keys to which the admin delegates some of its powers. Each role left
+    /// unset falls back to the admin.
+    pub roles: VestingRoles,
+    /// A key which, besides the vestee and the admin, can cancel queued
+    /// admin actions. The default pubkey means none.
+    pub guardian: Pubkey,
+    /// How long admin actions stay queued before they can be executed, see
+    /// [`QueuedAdminAction`]. Zero means no timelock, in which case the
+    /// actions take effect immediately.
+    pub action_delay_secs: i64,
+    /// A new vestee wallet requested by the owner of the current one, which
+    /// awaits the approval of the wallet manager. The default pubkey means
+    /// none.
+    pub pending_vestee_wallet: Pubkey,
+    /// If true, the owner of the vestee wallet changes it immediately rather
+    /// than requesting the approval of the wallet manager.
+    pub immediate_vestee_wallet_change: bool,
+    /// Keys to which the owner of the vestee wallet gives some of its powers,
+    /// see [`VesteeOperator`]. Unused slots have the default key.
+    pub operators: [VesteeOperator; 3],
+    /// If set, withdrawals go to any token account of the vesting mint owned
+    /// by this key rather than only to the vestee wallet, see
+    /// [`VestingOptions::withdraw_to_owner`]. Follows the owner of the vestee
+    /// wallet whenever the vestee wallet changes. The default pubkey means
+    /// that withdrawals go to the vestee wallet.
+    pub beneficiary_owner: Pubkey,
+    /// Withdrawn tokens which could not be delivered to the vestee wallet and
+    /// stay in the vault until the beneficiary pulls them to a wallet which
+    /// can receive them. They are not part of [`Vesting::vault_balance`].
+    pub claimable_balance: TokenAmount,
+    /// If true, withdrawals and the wallet changes of the vestee are blocked
+    /// until the admin or the guardian unfreezes the vesting account. Vesting
+    /// keeps accruing meanwhile.
+    pub frozen: bool,
+    /// Whether the vesting account is listed in the [`VestingRegistry`] pages
+    /// of its admin and of its vestee, from which it must then be removed
+    /// when closed. Accounts created without registry pages, or before the
+    /// registries were introduced, are not listed.
+    pub registered: bool,
+    /// The owner of [`Vesting::vestee_wallet`] at the time the wallet was
+    /// set, who acts as the vestee even if the wallet is later closed. The
+    /// default pubkey means that the vesting account predates this field,
+    /// see [`Vesting::vestee`].
+    pub vestee_wallet_owner: Pubkey,
+    /// Zeroed space reserved for future fields. New fields are carved out of
+    /// it between `version` and `reserved` so that the size of the account
+    /// stays the same, and their zero value must mean "unset". Fields which
+    /// do not fit are appended after `reserved` and the accounts migrated.
+    pub reserved: [u8; 18],
 }
 
 impl Vesting {
     pub const VAULT_PREFIX: &'static [u8; 5] = b"vault";
     pub const SIGNER_PDA_PREFIX: &'static [u8; 6] = b"signer";
+    pub const GRANT_PDA_PREFIX: &'static [u8; 5] = b"grant";
+    pub const MAX_BLACKOUT_WINDOWS: usize = 8;
+    /// Thirty days.
+    pub const MAX_BLACKOUT_WINDOW_SECS: i64 = 30 * 86_400;
+    /// Thirty days.
+    pub const MAX_ACTION_DELAY_SECS: i64 = 30 * 86_400;
+    /// How many operators the owner of the vestee wallet can register at
+    /// once, see [`Vesting::operators`].
+    pub const MAX_OPERATORS: usize = 3;
+    /// The rate limit window is split into this many buckets, plus the one
+    /// in progress, which is why [`Vesting::rate_limit_buckets`] has one more
+    /// slot.
+    pub const RATE_LIMIT_BUCKETS: usize = 8;
+    /// Bump this whenever the size of the account changes, and handle the
+    /// upgrade in [`Vesting::from_legacy_data`] if the zero value of the new
+    /// fields is not a valid default. Fields carved out of the reserved space
+    /// need no bump.
+    pub const CURRENT_VERSION: u8 = 1;
 
     pub fn space() -> usize {
         let discriminant = 8;
-        let admin = 32;
-        let vestee_wallet = 32;
-        let mint = 32;
-        let vault = 32;
-
-        let total_vesting = mem::size_of::<TokenAmount>();
-        let cumulative_vested = mem::size_of::<TokenAmount>();
-        let cumulative_withdrawn = mem::size_of::<TokenAmount>();
-        let vesting_vault_balance = mem::size_of::<TokenAmount>();
-        let unfunded_liabilities = mem::size_of::<TokenAmount>();
-
-        let start_ts = mem::size_of::<i32>();
-
-        let total_periods = mem::size_of::<u64>();
-        let cliff_periods = mem::size_of::<u64>();
-        let period_type = mem::size_of::<PeriodType>();
-
-        discriminant
-            + admin
-            + vestee_wallet
-            + mint
-            + vault
-            + mint
-            + total_vesting
-            + cumulative_vested
-            + cumulative_withdrawn
-            + vesting_vault_balance
-            + unfunded_liabilities
-            + start_ts
-            + total_periods
-            + cliff_periods
-            + period_type
+
+        // All fields are of fixed size, hence any value serializes into the
+        // same number of bytes as the default one.
+        let fields = Self::default()
+            .try_to_vec()
+            .expect("Vesting always serializes into a vec")
+            .len();
+
+        discriminant + fields
+    }
+
+    /// Upgrades the data of a [`Vesting`] account created by an older version
+    /// of the program, including the account discriminator.
+    ///
+    /// Every older layout is a prefix of the current one, and the fields
+    /// added since then read as zero, which means "unset". Hence the data is
+    /// zero padded to [`Vesting::space`] and deserialized as is.
+    pub fn from_legacy_data(data: &[u8]) -> Result<Self> {
+        if data.len() > Self::space() {
+            return Err(error!(err::acc(
+                "Vesting account is larger than the current layout"
+            )));
+        }
+
+        let mut padded = data.to_vec();
+        padded.resize(Self::space(), 0);
+        let mut vesting = Self::try_deserialize(&mut padded.as_slice())?;
+
+        if vesting.version >= Self::CURRENT_VERSION {
+            return Err(error!(err::acc(
+                "Vesting account is already of the current version"
+            )));
+        }
+        vesting.version = Self::CURRENT_VERSION;
+
+        Ok(vesting)
+    }
+
+    /// Sets the parameters of the vesting schedule of a newly created
+    /// [`Vesting`] account, validating them first.
+    ///
+    /// The period type is given as an `u32` which maps to the [`PeriodType`]
+    /// enum as per [`PeriodType::from_u32`]. As of this contract version only
+    /// the types `Daily` and `Monthly` are supported.
+    ///
+    /// Only `create_vesting_schedule` uses this method, such that it keeps
+    /// accepting the schedules it always did. The other create endpoints
+    /// validate the schedule with [`Vesting::set_schedule_params`].
+    pub fn set_schedule(
+        &mut self,
+        vesting_amount: TokenAmount,
+        start_ts: TimeStamp,
+        cliff_periods: u64,
+        total_periods: u64,
+        period_type: u32,
+    ) -> Result<()> {
+        if period_type > 2 {
+            return Err(error!(err::arg(
+                "The current contract version only supports \
+                 vesting schedules with daily or monthly periods"
+            )));
+        }
+
+        if cliff_periods > total_periods {
+            return Err(error!(err::arg(
+                "The number of cliff periods cannot be higher than total number of periods"
+            )));
+        }
+
+        self.total_vesting = vesting_amount;
+
+        self.start_ts = start_ts;
+        self.total_periods = total_periods;
+        self.cliff_periods = cliff_periods;
+        self.period_type = PeriodType::from_u32(period_type)?;
+
+        Ok(())
+    }
+
+    /// Sets the parameters of the vesting schedule of a newly created
+    /// [`Vesting`] account like [`Vesting::set_schedule`], but validates
+    /// all of them up front with a precise error for each failure:
+    ///
+    /// - the vesting amount and the total number of periods must be positive
+    /// - the cliff cannot be longer than the whole schedule
+    /// - only the `Daily` and `Monthly` period types are supported
+    /// - the start must be within [`ScheduleParams::MAX_START_OFFSET_SECS`]
+    ///   of the current time
+    /// - the end of the schedule must be a representable date
+    pub fn set_schedule_params(&mut self, params: ScheduleParams, clock_ts: i64) -> Result<()> {
+        if params.vesting_amount.amount == 0 {
+            return Err(error!(TreasuryError::ZeroVestingAmount));
+        }
+
+        if params.total_periods == 0 {
+            return Err(error!(TreasuryError::ZeroTotalPeriods));
+        }
+
+        if params.cliff_periods > params.total_periods {
+            return Err(error!(TreasuryError::CliffExceedsTotalPeriods));
+        }
+
+        if !matches!(params.period_type, PeriodType::Daily | PeriodType::Monthly) {
+            return Err(error!(TreasuryError::UnsupportedPeriodType));
+        }
+
+        let start_offset = (params.start_ts.time as i128 - clock_ts as i128).abs();
+        if start_offset > ScheduleParams::MAX_START_OFFSET_SECS as i128 {
+            return Err(error!(TreasuryError::StartTimestampOutOfBounds));
+        }
+
+        self.total_vesting = params.vesting_amount;
+        self.start_ts = params.start_ts;
+        self.total_periods = params.total_periods;
+        self.cliff_periods = params.cliff_periods;
+        self.period_type = params.period_type;
+
+        // Fails if the end date overflows, in which case the vested tokens
+        // could never be computed
+        self.end_ts()?;
+
+        Ok(())
+    }
+
+    /// Applies the settings chosen at the creation of the [`Vesting`]
+    /// account. Must be called after [`Vesting::set_schedule`] because the
+    /// claim deadline is validated against the end of the schedule.
+    pub fn set_options(
+        &mut self,
+        options: VestingOptions,
+        vestee_wallet_owner: &Pubkey,
+    ) -> Result<()> {
+        self.set_claim_deadline(options.claim_deadline)?;
+        self.beneficiary_only = options.beneficiary_only;
+        self.set_rate_limit(options.rate_limit)?;
+        self.roles = options.roles;
+        self.set_timelock(options.action_delay_secs, options.guardian)?;
+        self.immediate_vestee_wallet_change = options.immediate_vestee_wallet_change;
+        if options.withdraw_to_owner {
+            self.beneficiary_owner = *vestee_wallet_owner;
+        }
+
+        Ok(())
     }
 
     /// Updates the field `cumulative_vested` in [`Vesting`] struct based
@@ -225,11 +436,18 @@ impl Vesting {
     /// and if the period is `Daily` it will shift by n days, where n is the
     /// argument `periods`
     pub fn shift_periods(&mut self, date: DateTime<Utc>, periods: u64) -> Result<DateTime<Utc>> {
+        // Duration panics if its milliseconds overflow an i64
+        const MAX_DAYS: u64 = (i64::MAX / 1_000 / 86_400) as u64;
+
         match self.period_type {
-            PeriodType::Daily => date
+            PeriodType::Daily if periods <= MAX_DAYS => date
                 .checked_add_signed(Duration::days(periods as i64))
-                .ok_or_else(|| error!(TreasuryError::InvariantViolation)),
-            PeriodType::Monthly => Ok(shift_months(date, periods as i32)),
+                .ok_or_else(|| error!(TreasuryError::ScheduleEndOverflow)),
+            PeriodType::Daily => Err(error!(TreasuryError::ScheduleEndOverflow)),
+            PeriodType::Monthly => i32::try_from(periods)
+                .ok()
+                .and_then(|months| checked_shift_months(date, months))
+                .ok_or_else(|| error!(TreasuryError::ScheduleEndOverflow)),
             _ => Err(error!(err::acc(
                 "Current program only supports Daily or Monthly PeriodType"
             ))),
@@ -251,355 +469,2105 @@ impl Vesting {
             .try_sub(Decimal::from(self.cumulative_withdrawn))?
             .try_round()?;
 
-        if self.vault_balance.amount >= liability {
-            // Because the whole current liability is funded or overfunded
-            return Ok(());
-        }
-        let unfunded_liability = liability - self.vault_balance.amount;
+        // Zero if the whole current liability is funded or overfunded
+        let unfunded_liability = liability.saturating_sub(self.vault_balance.amount);
 
         self.unfunded_liability = TokenAmount::new(unfunded_liability);
 
         Ok(())
     }
 
-    pub fn get_current_liability(&mut self) -> u64 {
-        self.cumulative_vested.amount - self.cumulative_withdrawn.amount
+    /// Records tokens transferred to the vault by a funder and recomputes the
+    /// unfunded liability.
+    pub fn record_funding(&mut self, funding_amount: TokenAmount) -> Result<()> {
+        let vault_balance = self
+            .vault_balance
+            .amount
+            .checked_add(funding_amount.amount)
+            .ok_or(TreasuryError::InvariantViolation)?;
+        self.vault_balance = TokenAmount::new(vault_balance);
+
+        // Since more tokens are being added to the vault we need to update how
+        // much of the vested tokens is currently unfunded, if any
+        self.update_unfunded_liability()
     }
-}
 
-#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
-pub enum PeriodType {
-    Daily,
-    Monthly,
-    Quarterly,
-    SemiAnnually,
-    Yearly,
-}
+    /// Sets the vault balance to the actual amount held by the vault token
+    /// account and recomputes the unfunded liability. Any tokens sent straight
+    /// to the vault count as funding. Returns the difference between the
+    /// actual and the previously recorded balance.
+    pub fn sync_vault_balance(&mut self, vault_amount: u64) -> Result<i128> {
+        let vault_amount = self.get_sweepable_amount(vault_amount);
+        let difference = vault_amount as i128 - self.vault_balance.amount as i128;
 
-impl Default for PeriodType {
-    fn default() -> Self {
-        PeriodType::Monthly
+        self.vault_balance = TokenAmount::new(vault_amount);
+        self.update_unfunded_liability()?;
+
+        Ok(difference)
     }
-}
 
-impl PeriodType {
-    pub fn from_u32(value: u32) -> Result<PeriodType> {
-        match value {
-            1 => Ok(PeriodType::Daily),
-            2 => Ok(PeriodType::Monthly),
-            3 => Ok(PeriodType::Quarterly),
-            4 => Ok(PeriodType::SemiAnnually),
-            5 => Ok(PeriodType::Yearly),
-            _ => Err(error!(err::arg("The period type enumeration is invalid"))),
-        }
+    /// Nominates the key which can take over the admin role by calling
+    /// [`Vesting::accept_admin`]. The default pubkey cancels the nomination.
+    pub fn propose_admin(&mut self, pending_admin: Pubkey) {
+        self.pending_admin = pending_admin;
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Hands the admin role over to the nominated key, which must be the
+    /// signer. Returns the previous admin.
+    pub fn accept_admin(&mut self, signer: &Pubkey) -> Result<Pubkey> {
+        if self.pending_admin == Pubkey::default() || self.pending_admin != *signer {
+            return Err(error!(err::acc(
+                "Signer is not the admin nominated for this vesting account"
+            )));
+        }
 
-    #[test]
-    fn it_does_not_update_vested_tokens_if_before_cliff_date() -> Result<()> {
-        let clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 14));
+        Ok(self.set_admin(self.pending_admin))
+    }
 
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 48,
-            cliff_periods: 12,
-            ..Default::default()
-        };
+    /// Sets the admin and clears any pending nomination. Returns the previous
+    /// admin.
+    pub fn set_admin(&mut self, admin: Pubkey) -> Pubkey {
+        self.pending_admin = Pubkey::default();
+        std::mem::replace(&mut self.admin, admin)
+    }
 
-        vesting.update_vested_tokens(clock.time)?;
+    /// The key which can refund the surplus of the vault.
+    pub fn funder(&self) -> Pubkey {
+        self.or_admin(self.roles.funder)
+    }
 
-        // Check that nothing has changed
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(0));
-        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
-        assert_eq!(vesting.cliff_periods, 12);
-        assert_eq!(vesting.total_periods, 48);
-        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+    /// The key which can change the vestee wallet.
+    pub fn wallet_manager(&self) -> Pubkey {
+        self.or_admin(self.roles.wallet_manager)
+    }
 
-        Ok(())
+    /// The key which can sweep the unclaimed tokens after the claim deadline.
+    pub fn revoker(&self) -> Pubkey {
+        self.or_admin(self.roles.revoker)
     }
 
-    #[test]
-    fn it_updates_vested_tokens_if_is_cliff_date() -> Result<()> {
-        let clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 15));
+    /// The key which can close the vesting account.
+    pub fn closer(&self) -> Pubkey {
+        self.or_admin(self.roles.closer)
+    }
 
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 48,
-            cliff_periods: 12,
-            ..Default::default()
-        };
+    fn or_admin(&self, role: Pubkey) -> Pubkey {
+        if role == Pubkey::default() {
+            self.admin
+        } else {
+            role
+        }
+    }
 
-        vesting.update_vested_tokens(clock.time)?;
+    /// Points the vesting account to a new vestee wallet, which must differ
+    /// from the current one, and drops any pending request of the vestee.
+    /// The withdrawal delegate and the operators were registered by the
+    /// previous owner, hence they are removed too. If withdrawals go to the
+    /// owner, the owner of the new vestee wallet becomes the beneficiary
+    /// owner. Returns the previous vestee wallet.
+    pub fn change_vestee_wallet(
+        &mut self,
+        vestee_wallet: Pubkey,
+        vestee_wallet_owner: Pubkey,
+    ) -> Result<Pubkey> {
+        if self.vestee_wallet == vestee_wallet {
+            return Err(error!(err::acc(
+                "The new vestee wallet is the same as the current vestee wallet"
+            )));
+        }
+        self.pending_vestee_wallet = Pubkey::default();
+        self.withdrawal_delegate = Pubkey::default();
+        self.operators = Default::default();
+        self.vestee_wallet_owner = vestee_wallet_owner;
+        if self.withdraws_to_owner() {
+            self.beneficiary_owner = vestee_wallet_owner;
+        }
 
-        // Check that cumulative vested amount is correct
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(2_500));
+        Ok(std::mem::replace(&mut self.vestee_wallet, vestee_wallet))
+    }
 
-        // Check that nothing else has changed
-        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
-        assert_eq!(vesting.cliff_periods, 12);
-        assert_eq!(vesting.total_periods, 48);
-        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+    /// Handles the request of the owner of the vestee wallet to move to a
+    /// new one. Depending on [`Vesting::immediate_vestee_wallet_change`] the
+    /// change applies at once, in which case the previous vestee wallet is
+    /// returned, or awaits the approval of the wallet manager.
+    pub fn request_vestee_wallet_change(
+        &mut self,
+        vestee_wallet: Pubkey,
+        vestee_wallet_owner: Pubkey,
+    ) -> Result<Option<Pubkey>> {
+        if self.immediate_vestee_wallet_change {
+            return self
+                .change_vestee_wallet(vestee_wallet, vestee_wallet_owner)
+                .map(Some);
+        }
 
-        Ok(())
+        if self.vestee_wallet == vestee_wallet {
+            return Err(error!(err::acc(
+                "The new vestee wallet is the same as the current vestee wallet"
+            )));
+        }
+        self.pending_vestee_wallet = vestee_wallet;
+
+        Ok(None)
     }
 
-    #[test]
-    fn it_updates_vested_tokens_when_no_cliff() -> Result<()> {
-        let clock = TimeStamp::new_dt(Utc.ymd(2020, 7, 15));
+    /// Applies the vestee wallet requested by the owner of the current one.
+    /// Returns the previous vestee wallet.
+    pub fn approve_vestee_wallet_change(
+        &mut self,
+        vestee_wallet: &Pubkey,
+        vestee_wallet_owner: Pubkey,
+    ) -> Result<Pubkey> {
+        if self.pending_vestee_wallet == Pubkey::default()
+            || self.pending_vestee_wallet != *vestee_wallet
+        {
+            return Err(error!(err::acc(
+                "The vestee wallet does not match the one requested by the vestee"
+            )));
+        }
 
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 48,
-            cliff_periods: 0,
-            ..Default::default()
-        };
+        self.change_vestee_wallet(*vestee_wallet, vestee_wallet_owner)
+    }
 
-        vesting.update_vested_tokens(clock.time)?;
+    pub fn withdraws_to_owner(&self) -> bool {
+        self.beneficiary_owner != Pubkey::default()
+    }
 
-        // Check that cumulative vested amount is correct
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(208));
+    /// Checks that the given account can be the destination of withdrawals,
+    /// that is the vestee wallet unless withdrawals go to the owner.
+    pub fn check_withdrawal_destination(&self, wallet: &Pubkey) -> Result<()> {
+        if !self.withdraws_to_owner() && *wallet != self.vestee_wallet {
+            return Err(error!(err::acc(
+                "Vestee wallet input does not match the \
+                vestee wallet in the vesting account"
+            )));
+        }
 
         Ok(())
     }
 
-    #[test]
-    fn it_updates_vested_tokens_if_after_cliff_date() -> Result<()> {
-        let clock = TimeStamp::new_dt(Utc.ymd(2022, 6, 15));
+    /// Checks that withdrawals which the given destination cannot receive may
+    /// be escrowed. If withdrawals go to the owner, that is only the case for
+    /// the vestee wallet and the associated token account of the beneficiary
+    /// owner, otherwise any account could be passed to force tokens into the
+    /// claimable balance.
+    pub fn check_escrow_destination(&self, wallet: &Pubkey) -> Result<()> {
+        if self.withdraws_to_owner()
+            && *wallet != self.vestee_wallet
+            && *wallet != get_associated_token_address(&self.beneficiary_owner, &self.mint)
+        {
+            return Err(error!(err::acc(
+                "Only the vestee wallet or the associated token account \
+                of the beneficiary owner can escrow withdrawals"
+            )));
+        }
 
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 48,
-            cliff_periods: 12,
-            ..Default::default()
+        Ok(())
+    }
+
+    /// Returns true if a token account of the given mint and owner can
+    /// receive withdrawals. Its mint must be the vesting mint and, if
+    /// withdrawals go to the owner, it must be owned by the beneficiary
+    /// owner. Otherwise it must still be owned by the recorded
+    /// [`Vesting::vestee_wallet_owner`], such that reassigning the vestee
+    /// wallet does not hand the withdrawals to its new owner.
+    pub fn can_receive_withdrawals(&self, wallet_mint: &Pubkey, wallet_owner: &Pubkey) -> bool {
+        let expected_owner = if self.withdraws_to_owner() {
+            self.beneficiary_owner
+        } else {
+            self.vestee_wallet_owner
         };
 
-        vesting.update_vested_tokens(clock.time)?;
+        *wallet_mint == self.mint
+            && (expected_owner == Pubkey::default() || *wallet_owner == expected_owner)
+    }
 
-        // Check that cumulative vested amount is correct
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(5_000));
+    /// Sets the delay of queued admin actions and the guardian who can cancel
+    /// them. The delay is at most [`Vesting::MAX_ACTION_DELAY_SECS`], such
+    /// that the admin actions cannot be locked forever.
+    pub fn set_timelock(&mut self, action_delay_secs: i64, guardian: Pubkey) -> Result<()> {
+        if !(0..=Self::MAX_ACTION_DELAY_SECS).contains(&action_delay_secs) {
+            return Err(error!(err::arg(format!(
+                "The action delay must be between 0 and {} seconds",
+                Self::MAX_ACTION_DELAY_SECS
+            ))));
+        }
 
-        // Check that nothing else has changed
-        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
-        assert_eq!(vesting.cliff_periods, 12);
-        assert_eq!(vesting.total_periods, 48);
-        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+        self.action_delay_secs = action_delay_secs;
+        self.guardian = guardian;
 
         Ok(())
     }
 
-    #[test]
-    fn it_updates_vested_tokens_when_whole_vesting_is_cliff() -> Result<()> {
-        let mut clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 14));
+    pub fn is_timelocked(&self) -> bool {
+        self.action_delay_secs != 0
+    }
 
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 12,
-            cliff_periods: 12,
-            ..Default::default()
-        };
+    /// Fails if the admin actions of this vesting account must be queued
+    /// rather than taking effect immediately.
+    pub fn check_not_timelocked(&self) -> Result<()> {
+        if self.is_timelocked() {
+            return Err(error!(TreasuryError::AdminActionTimelocked));
+        }
 
-        vesting.update_vested_tokens(clock.time)?;
-        // Check that cumulative vested amount is correct
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(0));
+        Ok(())
+    }
 
-        clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 15));
-        vesting.update_vested_tokens(clock.time)?;
-        // Check that cumulative vested amount is correct
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(10_000));
+    /// The owner of the vestee wallet as recorded in
+    /// [`Vesting::vestee_wallet_owner`]. Vesting accounts which predate the
+    /// record must be given their vestee wallet as the first remaining
+    /// account, whose owner is read instead.
+    pub fn vestee(&self, remaining_accounts: &[AccountInfo]) -> Result<Pubkey> {
+        if self.vestee_wallet_owner != Pubkey::default() {
+            return Ok(self.vestee_wallet_owner);
+        }
 
-        // Check that nothing else has changed
-        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
-        assert_eq!(vesting.cliff_periods, 12);
-        assert_eq!(vesting.total_periods, 12);
-        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+        let wallet_info = remaining_accounts.first().ok_or_else(|| {
+            error!(err::acc(
+                "The vestee wallet must be passed as a remaining account"
+            ))
+        })?;
+        if wallet_info.key() != self.vestee_wallet {
+            return Err(error!(err::acc(
+                "Vestee wallet input does not match the vestee wallet in the vesting account"
+            )));
+        }
 
-        Ok(())
+        Ok(Account::<TokenAccount>::try_from(wallet_info)?.owner)
     }
 
-    #[test]
-    fn it_stops_updating_vested_tokens_if_after_fully_vested() -> Result<()> {
-        let clock = TimeStamp::new_dt(Utc.ymd(2024, 6, 15));
+    /// Returns true if the given signer can cancel a queued admin action,
+    /// that is the owner of the vestee wallet, an operator allowed to do so,
+    /// the guardian or the admin. The vestee and their operators keep this
+    /// right while the vesting account is frozen, otherwise freezing first
+    /// would let the admin change the vestee wallet without a veto.
+    pub fn is_action_cancel_authorized(
+        &self,
+        signer: &Pubkey,
+        vestee_wallet_owner: &Pubkey,
+        clock_ts: i64,
+    ) -> bool {
+        let is_vestee = signer == vestee_wallet_owner
+            || self.is_operator(signer, VesteeOperator::CANCEL_ADMIN_ACTION, clock_ts);
+
+        is_vestee || self.is_guardian_or_admin(signer)
+    }
 
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 48,
-            cliff_periods: 12,
-            ..Default::default()
-        };
+    fn is_guardian_or_admin(&self, signer: &Pubkey) -> bool {
+        *signer == self.admin || (self.guardian != Pubkey::default() && *signer == self.guardian)
+    }
 
-        vesting.update_vested_tokens(clock.time)?;
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(10_000));
+    /// Returns true if the given signer can freeze or unfreeze the vesting
+    /// account, that is the guardian or the admin.
+    pub fn is_freeze_authorized(&self, signer: &Pubkey) -> bool {
+        self.is_guardian_or_admin(signer)
+    }
 
-        let clock = TimeStamp::new_dt(Utc.ymd(2030, 6, 15));
-        vesting.update_vested_tokens(clock.time)?;
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(10_000));
+    /// Freezes or unfreezes the vesting account, which must not be in the
+    /// requested state already.
+    pub fn set_frozen(&mut self, frozen: bool) -> Result<()> {
+        if self.frozen == frozen {
+            return Err(error!(err::acc(if frozen {
+                "The vesting account is already frozen"
+            } else {
+                "The vesting account is not frozen"
+            })));
+        }
+
+        self.frozen = frozen;
 
         Ok(())
     }
 
-    #[test]
-    fn it_updates_vested_tokens_monthly() -> Result<()> {
-        let mut vesting = Vesting {
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
-            total_periods: 48,
-            cliff_periods: 12,
-            ..Default::default()
-        };
+    /// Fails if the vesting account is frozen, which blocks withdrawals,
+    /// sweeps and the changes of the vestee to their wallet, delegate and
+    /// operators.
+    pub fn check_not_frozen(&self) -> Result<()> {
+        if self.frozen {
+            return Err(error!(TreasuryError::VestingFrozen));
+        }
 
-        let mut clock;
-        let mut current_month = 7;
-        let mut current_year = 2020;
-        for i in 1..=48 {
-            clock = TimeStamp::new_dt(Utc.ymd(current_year, current_month, 20));
+        Ok(())
+    }
+
+    /// Adds an operator, or replaces the permissions and the expiry of the
+    /// operator with the same key. An operator without any permissions is
+    /// removed. Slots of expired operators are reused.
+    pub fn set_operator(&mut self, operator: VesteeOperator, clock_ts: i64) -> Result<()> {
+        if operator.key == Pubkey::default() {
+            return Err(error!(err::arg(
+                "The operator cannot be the default pubkey"
+            )));
+        }
+
+        if operator.permissions & !VesteeOperator::ALL != 0 {
+            return Err(error!(err::arg("Unknown operator permissions")));
+        }
+
+        if operator.expires_at.time != 0 && operator.expires_at.time <= clock_ts {
+            return Err(error!(err::arg(
+                "The operator expiry must be in the future"
+            )));
+        }
+
+        if let Some(slot) = self.operators.iter_mut().find(|o| o.key == operator.key) {
+            *slot = if operator.permissions == 0 {
+                VesteeOperator::default()
+            } else {
+                operator
+            };
+
+            return Ok(());
+        }
+
+        if operator.permissions == 0 {
+            return Err(error!(err::arg("There is no such operator to remove")));
+        }
+
+        let slot = self
+            .operators
+            .iter_mut()
+            .find(|o| o.key == Pubkey::default() || o.is_expired(clock_ts))
+            .ok_or_else(|| {
+                error!(err::arg(format!(
+                    "A vesting account can have at most {} operators",
+                    Self::MAX_OPERATORS
+                )))
+            })?;
+        *slot = operator;
+
+        Ok(())
+    }
+
+    /// Returns true if the given signer is an operator which has not expired
+    /// and holds all of the given permissions.
+    pub fn is_operator(&self, signer: &Pubkey, permissions: u8, clock_ts: i64) -> bool {
+        *signer != Pubkey::default()
+            && self
+                .operators
+                .iter()
+                .any(|o| o.key == *signer && o.has_permissions(permissions, clock_ts))
+    }
+
+    /// Returns the amount of tokens in the vault above the remaining
+    /// obligation of the schedule, that is the tokens which have not been
+    /// withdrawn yet out of the total vesting amount. These tokens can be
+    /// returned without touching the entitlement of the vestee.
+    pub fn get_surplus(&self) -> u64 {
+        let remaining_obligation = self
+            .total_vesting
+            .amount
+            .saturating_sub(self.cumulative_withdrawn.amount);
+
+        self.vault_balance
+            .amount
+            .saturating_sub(remaining_obligation)
+    }
+
+    /// Returns how many of the given tokens in the vault are not held for the
+    /// beneficiary as the claimable balance.
+    pub fn get_sweepable_amount(&self, vault_amount: u64) -> u64 {
+        vault_amount.saturating_sub(self.claimable_balance.amount)
+    }
+
+    /// Records a withdrawal of tokens out of the vault balance. If the tokens
+    /// could not be delivered, they are added to the claimable balance and
+    /// stay in the vault.
+    pub fn record_withdrawal(&mut self, amount: TokenAmount, delivered: bool) {
+        // Note that we don't need to update unfunded liabilities because the
+        // delta by which the vault balance decreases is offset by the delta by
+        // which the cumulative withdrawn amount increases (same delta)
+        self.vault_balance = TokenAmount::new(self.vault_balance.amount - amount.amount);
+        self.cumulative_withdrawn =
+            TokenAmount::new(self.cumulative_withdrawn.amount + amount.amount);
+
+        if !delivered {
+            self.claimable_balance =
+                TokenAmount::new(self.claimable_balance.amount + amount.amount);
+        }
+    }
+
+    /// Empties the claimable balance, returning the amount to be delivered
+    /// to the beneficiary.
+    pub fn take_claimable_balance(&mut self) -> Result<TokenAmount> {
+        if self.claimable_balance.amount == 0 {
+            return Err(error!(err::acc(
+                "There is no claimable balance to withdraw"
+            )));
+        }
+
+        Ok(std::mem::take(&mut self.claimable_balance))
+    }
+
+    pub fn get_current_liability(&mut self) -> u64 {
+        self.cumulative_vested.amount - self.cumulative_withdrawn.amount
+    }
+
+    /// Returns the timestamp at which all the tokens of the schedule are
+    /// vested.
+    pub fn end_ts(&mut self) -> Result<TimeStamp> {
+        let start_dt: DateTime<Utc> =
+            DateTime::from_utc(NaiveDateTime::from_timestamp(self.start_ts.time, 0), Utc);
+
+        let end_dt = self.shift_periods(start_dt, self.total_periods)?;
+
+        Ok(TimeStamp::new(end_dt.timestamp()))
+    }
+
+    /// Sets the claim deadline of the vesting account. The deadline cannot be
+    /// earlier than the end of the vesting schedule, otherwise the vestee
+    /// would lose tokens before they even vest. A zero deadline means that
+    /// the tokens can be claimed indefinitely.
+    pub fn set_claim_deadline(&mut self, claim_deadline: TimeStamp) -> Result<()> {
+        if claim_deadline.time != 0 && claim_deadline < self.end_ts()? {
+            return Err(error!(err::arg(
+                "The claim deadline cannot be before the end of the vesting schedule"
+            )));
+        }
+
+        self.claim_deadline = claim_deadline;
+
+        Ok(())
+    }
+
+    /// Returns true if the vesting account has a claim deadline and the given
+    /// timestamp is at or after it.
+    pub fn is_past_claim_deadline(&self, clock_ts: i64) -> bool {
+        self.claim_deadline.time != 0 && clock_ts >= self.claim_deadline.time
+    }
+
+    /// Fails unless the claim deadline has passed, after which the unclaimed
+    /// tokens can be swept from the vault. A frozen vesting account cannot be
+    /// swept, since the vestee could not withdraw before the deadline.
+    pub fn check_can_sweep(&self, clock_ts: i64) -> Result<()> {
+        self.check_not_frozen()?;
+
+        if !self.is_past_claim_deadline(clock_ts) {
+            return Err(error!(err::acc(
+                "The claim deadline of this vesting account has not passed yet"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the given signer is allowed to withdraw the vested
+    /// tokens to the vestee wallet owned by `vestee_wallet_owner`. Unless the
+    /// vesting account is in the beneficiary only mode, anyone is allowed.
+    /// Otherwise the owner, the withdrawal delegate or an operator allowed to
+    /// withdraw must sign.
+    pub fn is_withdrawal_authorized(
+        &self,
+        signer: &Pubkey,
+        vestee_wallet_owner: &Pubkey,
+        clock_ts: i64,
+    ) -> bool {
+        if !self.beneficiary_only {
+            return true;
+        }
+
+        signer == vestee_wallet_owner
+            || (self.withdrawal_delegate != Pubkey::default()
+                && *signer == self.withdrawal_delegate)
+            || self.is_operator(signer, VesteeOperator::WITHDRAW, clock_ts)
+    }
+
+    /// Sets the withdrawal rate limit of the vesting account. A zero window
+    /// disables the rate limit, otherwise both the window and the maximum
+    /// amount must be positive.
+    pub fn set_rate_limit(&mut self, rate_limit: RateLimit) -> Result<()> {
+        if rate_limit.window_secs < 0 {
+            return Err(error!(err::arg("The rate limit window cannot be negative")));
+        }
+
+        if rate_limit.is_enabled() && rate_limit.max_amount.amount == 0 {
+            return Err(error!(err::arg(
+                "The rate limit maximum amount must be positive"
+            )));
+        }
+
+        self.rate_limit = rate_limit;
+
+        Ok(())
+    }
+
+    /// Records a withdrawal of `amount` at `clock_ts` against the rate limit,
+    /// failing if it would take the amount withdrawn within the window
+    /// ending at `clock_ts` over the maximum.
+    ///
+    /// The window rolls with the clock. Withdrawals are added up in buckets
+    /// of [`RateLimit::bucket_secs`], and a bucket only stops counting once
+    /// all of it is older than the window. Hence no window of the given
+    /// length ever sees more than the maximum, while a withdrawal may count
+    /// for up to one bucket longer than the window.
+    pub fn apply_rate_limit(&mut self, clock_ts: i64, amount: u64) -> Result<()> {
+        if !self.rate_limit.is_enabled() {
+            return Ok(());
+        }
+
+        let already_withdrawn = self.get_rate_limit_withdrawn(clock_ts);
+        let withdrawn = already_withdrawn
+            .checked_add(amount)
+            .ok_or_else(|| error!(TreasuryError::InvariantViolation))?;
+
+        if withdrawn > self.rate_limit.max_amount.amount {
+            msg!(
+                "At most {} tokens can be withdrawn within {} seconds, {} already were",
+                self.rate_limit.max_amount.amount,
+                self.rate_limit.window_secs,
+                already_withdrawn
+            );
+            return Err(error!(TreasuryError::WithdrawalRateLimitExceeded));
+        }
+
+        // Empty the slots of the buckets which the clock moved past, as they
+        // are reused for the new buckets
+        let bucket = self.rate_limit.bucket_of(clock_ts);
+        let slots = self.rate_limit_buckets.len() as i64;
+        if bucket > self.rate_limit_head_bucket {
+            let stale = (bucket - self.rate_limit_head_bucket).min(slots);
+            for age in 0..stale {
+                let slot = (bucket - age).rem_euclid(slots) as usize;
+                self.rate_limit_buckets[slot] = TokenAmount::new(0);
+            }
+            self.rate_limit_head_bucket = bucket;
+        }
+
+        let head =
+            &mut self.rate_limit_buckets[self.rate_limit_head_bucket.rem_euclid(slots) as usize];
+        *head = TokenAmount::new(head.amount + amount);
+
+        Ok(())
+    }
+
+    /// Returns the amount withdrawn within the buckets of the rate limit
+    /// which still overlap the window ending at `clock_ts`.
+    pub fn get_rate_limit_withdrawn(&self, clock_ts: i64) -> u64 {
+        let slots = self.rate_limit_buckets.len() as i64;
+        let oldest_bucket = self.rate_limit.bucket_of(clock_ts) - (slots - 1);
+
+        (0..slots)
+            .map(|age| self.rate_limit_head_bucket - age)
+            .filter(|bucket| *bucket >= oldest_bucket)
+            .map(|bucket| self.rate_limit_buckets[bucket.rem_euclid(slots) as usize].amount)
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Returns how many more tokens can be withdrawn at `clock_ts` before
+    /// hitting the rate limit, if any.
+    pub fn get_rate_limit_allowance(&self, clock_ts: i64) -> u64 {
+        if !self.rate_limit.is_enabled() {
+            return u64::MAX;
+        }
+
+        self.rate_limit
+            .max_amount
+            .amount
+            .saturating_sub(self.get_rate_limit_withdrawn(clock_ts))
+    }
+
+    /// Returns the maximum amount that can be withdrawn at `clock_ts`, which
+    /// is the current liability capped by the vault balance and by the rate
+    /// limit allowance.
+    pub fn get_max_withdrawable(&mut self, clock_ts: i64) -> TokenAmount {
+        let amount = self
+            .get_current_liability()
+            .min(self.vault_balance.amount)
+            .min(self.get_rate_limit_allowance(clock_ts));
+
+        TokenAmount::new(amount)
+    }
+
+    /// Replaces the blackout windows of the vesting account. Passing an empty
+    /// list removes all windows. Windows must not be empty, i.e. their start
+    /// must be strictly before their end, and must not be longer than
+    /// [`Vesting::MAX_BLACKOUT_WINDOW_SECS`]. They must be sorted and must not
+    /// overlap, and they must end before the claim deadline, if any, such
+    /// that the admin cannot block the vestee until the vested tokens can be
+    /// swept.
+    pub fn set_blackout_windows(&mut self, windows: &[BlackoutWindow]) -> Result<()> {
+        if windows.len() > Self::MAX_BLACKOUT_WINDOWS {
+            return Err(error!(err::arg(format!(
+                "At most {} blackout windows are supported",
+                Self::MAX_BLACKOUT_WINDOWS
+            ))));
+        }
+
+        if windows.iter().any(|window| window.start >= window.end) {
+            return Err(error!(err::arg(
+                "Blackout window start must be before its end"
+            )));
+        }
+
+        if windows
+            .iter()
+            .any(|window| window.duration_secs() > Self::MAX_BLACKOUT_WINDOW_SECS)
+        {
+            return Err(error!(err::arg(format!(
+                "A blackout window can be at most {} seconds long",
+                Self::MAX_BLACKOUT_WINDOW_SECS
+            ))));
+        }
+
+        if windows.windows(2).any(|pair| pair[0].end > pair[1].start) {
+            return Err(error!(err::arg(
+                "Blackout windows must be sorted by their start and must not overlap"
+            )));
+        }
+
+        if self.claim_deadline.time != 0
+            && windows
+                .iter()
+                .any(|window| window.end >= self.claim_deadline)
+        {
+            return Err(error!(err::arg(
+                "Blackout windows must end before the claim deadline"
+            )));
+        }
+
+        self.blackout_windows = Default::default();
+        self.blackout_windows[..windows.len()].copy_from_slice(windows);
+
+        Ok(())
+    }
+
+    /// Returns true if the given timestamp falls within any of the blackout
+    /// windows of the vesting account.
+    pub fn is_in_blackout(&self, clock_ts: i64) -> bool {
+        self.blackout_windows
+            .iter()
+            .any(|window| window.is_active(clock_ts))
+    }
+}
+
+/// A period of time, from `start` inclusive to `end` exclusive, during which
+/// vested tokens cannot be withdrawn.
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlackoutWindow {
+    pub start: TimeStamp,
+    pub end: TimeStamp,
+}
+
+impl BlackoutWindow {
+    pub fn new(start: TimeStamp, end: TimeStamp) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_active(&self, clock_ts: i64) -> bool {
+        self.start.time <= clock_ts && clock_ts < self.end.time
+    }
+
+    pub fn duration_secs(&self) -> i64 {
+        self.end.time.saturating_sub(self.start.time)
+    }
+}
+
+/// Limits the amount of tokens that can be withdrawn within any window of
+/// time of the given length, e.g. at most 1 000 tokens per 7 days. A zero
+/// window means no limit.
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RateLimit {
+    /// Length of the window in seconds.
+    pub window_secs: i64,
+    /// Maximum amount of tokens that can be withdrawn within a window.
+    pub max_amount: TokenAmount,
+}
+
+impl RateLimit {
+    pub fn new(window_secs: i64, max_amount: TokenAmount) -> Self {
+        Self {
+            window_secs,
+            max_amount,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.window_secs != 0
+    }
+
+    /// The length of a bucket of the rolling window, rounded up such that
+    /// [`Vesting::RATE_LIMIT_BUCKETS`] buckets cover the whole window.
+    pub fn bucket_secs(&self) -> i64 {
+        let buckets = Vesting::RATE_LIMIT_BUCKETS as i64;
+
+        (self.window_secs / buckets + (self.window_secs % buckets != 0) as i64).max(1)
+    }
+
+    /// Returns the index of the bucket which `clock_ts` falls into.
+    pub fn bucket_of(&self, clock_ts: i64) -> i64 {
+        clock_ts.div_euclid(self.bucket_secs())
+    }
+}
+
+/// A key to which the owner of the vestee wallet gives some of its powers,
+/// e.g. a custodian or a tax service. Operators never get to change the
+/// vestee wallet, the withdrawal delegate or the operators, and withdrawals
+/// they sign still go to the vestee wallet.
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct VesteeOperator {
+    pub key: Pubkey,
+    /// Bit set of [`VesteeOperator::WITHDRAW`] and
+    /// [`VesteeOperator::CANCEL_ADMIN_ACTION`].
+    pub permissions: u8,
+    /// From this time on the operator has no permissions. Zero means that
+    /// the operator does not expire.
+    pub expires_at: TimeStamp,
+}
+
+impl VesteeOperator {
+    /// Can sign withdrawals when the vesting account is in the beneficiary
+    /// only mode.
+    pub const WITHDRAW: u8 = 1 << 0;
+    /// Can cancel queued admin actions with `cancel_admin_action`.
+    pub const CANCEL_ADMIN_ACTION: u8 = 1 << 1;
+    pub const ALL: u8 = Self::WITHDRAW | Self::CANCEL_ADMIN_ACTION;
+
+    pub fn is_expired(&self, clock_ts: i64) -> bool {
+        self.expires_at.time != 0 && self.expires_at.time <= clock_ts
+    }
+
+    pub fn has_permissions(&self, permissions: u8, clock_ts: i64) -> bool {
+        self.permissions & permissions == permissions && !self.is_expired(clock_ts)
+    }
+}
+
+/// Keys which hold a single power of the admin of a [`Vesting`] account, so
+/// that different powers can be given to different keys. The default pubkey
+/// means that the role is held by the admin.
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct VestingRoles {
+    /// Can refund the surplus of the vault with `refund_surplus`.
+    pub funder: Pubkey,
+    /// Can change the vestee wallet with `change_vestee_wallet`.
+    pub wallet_manager: Pubkey,
+    /// Can sweep the unclaimed tokens with `sweep_unclaimed_tokens`.
+    pub revoker: Pubkey,
+    /// Can close the vesting account with `close_vesting_schedule`.
+    pub closer: Pubkey,
+}
+
+/// The parameters of a vesting schedule as accepted by
+/// `create_vesting_schedule_v2`, see [`Vesting::set_schedule_params`].
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ScheduleParams {
+    /// See [`Vesting::total_vesting`].
+    pub vesting_amount: TokenAmount,
+    /// See [`Vesting::start_ts`].
+    pub start_ts: TimeStamp,
+    /// See [`Vesting::cliff_periods`].
+    pub cliff_periods: u64,
+    /// See [`Vesting::total_periods`].
+    pub total_periods: u64,
+    /// See [`Vesting::period_type`].
+    pub period_type: PeriodType,
+}
+
+impl ScheduleParams {
+    /// How far the start of a schedule can be from the time of its creation,
+    /// in either direction. Ten years of 365 days.
+    pub const MAX_START_OFFSET_SECS: i64 = 10 * 365 * 86_400;
+
+    /// Builds the parameters from the arguments of the create endpoints which
+    /// take the period type as an `u32`, see [`PeriodType::from_u32`].
+    pub fn new(
+        vesting_amount: TokenAmount,
+        start_ts: TimeStamp,
+        cliff_periods: u64,
+        total_periods: u64,
+        period_type: u32,
+    ) -> Result<Self> {
+        Ok(Self {
+            vesting_amount,
+            start_ts,
+            cliff_periods,
+            total_periods,
+            period_type: PeriodType::from_u32(period_type)?,
+        })
+    }
+}
+
+/// Settings of a [`Vesting`] account which are chosen by the admin when the
+/// vesting schedule is created. Only the roles can be changed afterwards.
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct VestingOptions {
+    /// See [`Vesting::claim_deadline`], zero means no deadline.
+    pub claim_deadline: TimeStamp,
+    /// See [`Vesting::beneficiary_only`].
+    pub beneficiary_only: bool,
+    /// See [`Vesting::rate_limit`], a zero window means no limit.
+    pub rate_limit: RateLimit,
+    /// If true, the vault is the associated token account of the vesting
+    /// signer rather than the PDA derived from [`Vesting::VAULT_PREFIX`].
+    pub associated_vault: bool,
+    /// See [`Vesting::roles`], unset roles are held by the admin.
+    pub roles: VestingRoles,
+    /// See [`Vesting::action_delay_secs`], zero means no timelock.
+    pub action_delay_secs: i64,
+    /// See [`Vesting::guardian`], the default pubkey means none.
+    pub guardian: Pubkey,
+    /// See [`Vesting::immediate_vestee_wallet_change`].
+    pub immediate_vestee_wallet_change: bool,
+    /// If true, the owner of the vestee wallet is recorded as the
+    /// [`Vesting::beneficiary_owner`], such that withdrawals can go to any of
+    /// its token accounts of the vesting mint.
+    pub withdraw_to_owner: bool,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PeriodType {
+    Daily,
+    Monthly,
+    Quarterly,
+    SemiAnnually,
+    Yearly,
+}
+
+impl Default for PeriodType {
+    fn default() -> Self {
+        PeriodType::Monthly
+    }
+}
+
+impl PeriodType {
+    pub fn from_u32(value: u32) -> Result<PeriodType> {
+        match value {
+            1 => Ok(PeriodType::Daily),
+            2 => Ok(PeriodType::Monthly),
+            3 => Ok(PeriodType::Quarterly),
+            4 => Ok(PeriodType::SemiAnnually),
+            5 => Ok(PeriodType::Yearly),
+            _ => Err(error!(err::arg("The period type enumeration is invalid"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_does_not_update_vested_tokens_if_before_cliff_date() -> Result<()> {
+        let clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 14));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 48,
+            cliff_periods: 12,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+
+        // Check that nothing has changed
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(0));
+        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
+        assert_eq!(vesting.cliff_periods, 12);
+        assert_eq!(vesting.total_periods, 48);
+        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_if_is_cliff_date() -> Result<()> {
+        let clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 15));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 48,
+            cliff_periods: 12,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+
+        // Check that cumulative vested amount is correct
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(2_500));
+
+        // Check that nothing else has changed
+        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
+        assert_eq!(vesting.cliff_periods, 12);
+        assert_eq!(vesting.total_periods, 48);
+        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_when_no_cliff() -> Result<()> {
+        let clock = TimeStamp::new_dt(Utc.ymd(2020, 7, 15));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 48,
+            cliff_periods: 0,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+
+        // Check that cumulative vested amount is correct
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(208));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_if_after_cliff_date() -> Result<()> {
+        let clock = TimeStamp::new_dt(Utc.ymd(2022, 6, 15));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 48,
+            cliff_periods: 12,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+
+        // Check that cumulative vested amount is correct
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(5_000));
+
+        // Check that nothing else has changed
+        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
+        assert_eq!(vesting.cliff_periods, 12);
+        assert_eq!(vesting.total_periods, 48);
+        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_when_whole_vesting_is_cliff() -> Result<()> {
+        let mut clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 14));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 12,
+            cliff_periods: 12,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+        // Check that cumulative vested amount is correct
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(0));
+
+        clock = TimeStamp::new_dt(Utc.ymd(2021, 6, 15));
+        vesting.update_vested_tokens(clock.time)?;
+        // Check that cumulative vested amount is correct
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(10_000));
+
+        // Check that nothing else has changed
+        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
+        assert_eq!(vesting.cliff_periods, 12);
+        assert_eq!(vesting.total_periods, 12);
+        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 6, 15)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_stops_updating_vested_tokens_if_after_fully_vested() -> Result<()> {
+        let clock = TimeStamp::new_dt(Utc.ymd(2024, 6, 15));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 48,
+            cliff_periods: 12,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(10_000));
+
+        let clock = TimeStamp::new_dt(Utc.ymd(2030, 6, 15));
+        vesting.update_vested_tokens(clock.time)?;
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(10_000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_monthly() -> Result<()> {
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 15)),
+            total_periods: 48,
+            cliff_periods: 12,
+            ..Default::default()
+        };
+
+        let mut clock;
+        let mut current_month = 7;
+        let mut current_year = 2020;
+        for i in 1..=48 {
+            clock = TimeStamp::new_dt(Utc.ymd(current_year, current_month, 20));
+            vesting.update_vested_tokens(clock.time)?;
+
+            // Check that cumulative vested amount is correct
+            let vested_tokens = if i <= 11 { 0 } else { i * 10_000 / 48 };
+
+            assert_eq!(vesting.cumulative_vested, TokenAmount::new(vested_tokens));
+
+            // Increment month and year datetime
+            current_year = if current_month == 12 {
+                current_year + 1
+            } else {
+                current_year
+            };
+            current_month = if current_month < 12 {
+                current_month + 1
+            } else {
+                1
+            };
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_daily() -> Result<()> {
+        let mut vesting = Vesting {
+            period_type: PeriodType::Daily,
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 10)),
+            total_periods: 100,
+            cliff_periods: 25,
+            ..Default::default()
+        };
+
+        let mut current_dt: DateTime<Utc> =
+            DateTime::from_utc(NaiveDateTime::from_timestamp(vesting.start_ts.time, 0), Utc);
+
+        let cliff_dt = current_dt
+            .checked_add_signed(Duration::days(vesting.cliff_periods as i64))
+            .unwrap();
+
+        let mut clock;
+        for i in 1..=110 {
+            current_dt = current_dt.checked_add_signed(Duration::days(1)).unwrap();
+
+            clock = TimeStamp::new_dt(current_dt.date());
             vesting.update_vested_tokens(clock.time)?;
 
-            // Check that cumulative vested amount is correct
-            let vested_tokens = if i <= 11 { 0 } else { i * 10_000 / 48 };
+            // Check that cumulative vested amount is correct
+            let vested_tokens = if current_dt < cliff_dt {
+                0
+            } else if i < 100 {
+                i * 10_000 / 100
+            } else {
+                10_000
+            };
+
+            assert_eq!(vesting.cumulative_vested, TokenAmount::new(vested_tokens));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_vested_tokens_when_vesting_day_is_eom() -> Result<()> {
+        let clock = TimeStamp::new_dt(Utc.ymd(2022, 2, 28));
+
+        let mut vesting = Vesting {
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(0),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2022, 1, 31)),
+            total_periods: 48,
+            cliff_periods: 0,
+            ..Default::default()
+        };
+
+        vesting.update_vested_tokens(clock.time)?;
+
+        // Check that cumulative vested amount is correct
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(0));
+
+        let clock = TimeStamp::new_dt(Utc.ymd(2022, 3, 1));
+        vesting.update_vested_tokens(clock.time)?;
+        assert_eq!(vesting.cumulative_vested, TokenAmount::new(208));
+        Ok(())
+    }
+
+    #[test]
+    fn it_updates_unfunded_liabilities_when_positive() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(5_000),
+            cumulative_withdrawn: TokenAmount::new(2500),
+            vault_balance: TokenAmount::new(500),
+            ..Default::default()
+        };
+
+        vesting.update_unfunded_liability()?;
+
+        // Unfunded liability = 5_000 - 2_500 - 500
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(2_000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_does_not_update_unfunded_liabilities_when_none() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(5_000),
+            cumulative_withdrawn: TokenAmount::new(5_000),
+            vault_balance: TokenAmount::new(0),
+            ..Default::default()
+        };
+
+        vesting.update_unfunded_liability()?;
+
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_does_not_update_unfunded_liabilities_when_overfunded() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(5_000),
+            cumulative_withdrawn: TokenAmount::new(0),
+            vault_balance: TokenAmount::new(10_000),
+            ..Default::default()
+        };
+
+        vesting.update_unfunded_liability()?;
+
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_resets_unfunded_liabilities_when_funded() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(5_000),
+            cumulative_withdrawn: TokenAmount::new(0),
+            vault_balance: TokenAmount::new(1_000),
+            ..Default::default()
+        };
+
+        vesting.update_unfunded_liability()?;
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(4_000));
+
+        vesting.vault_balance = TokenAmount::new(5_000);
+        vesting.update_unfunded_liability()?;
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_records_funding() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(5_000),
+            unfunded_liability: TokenAmount::new(5_000),
+            ..Default::default()
+        };
+
+        vesting.record_funding(TokenAmount::new(3_000))?;
+        assert_eq!(vesting.vault_balance, TokenAmount::new(3_000));
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(2_000));
+
+        vesting.vault_balance = TokenAmount::new(u64::MAX);
+        assert!(vesting.record_funding(TokenAmount::new(1)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_syncs_vault_balance() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(5_000),
+            cumulative_withdrawn: TokenAmount::new(1_000),
+            vault_balance: TokenAmount::new(1_000),
+            unfunded_liability: TokenAmount::new(3_000),
+            ..Default::default()
+        };
+
+        // Tokens sent straight to the vault count as funding
+        assert_eq!(vesting.sync_vault_balance(4_500)?, 3_500);
+        assert_eq!(vesting.vault_balance, TokenAmount::new(4_500));
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(0));
+
+        // And the liability becomes unfunded again if the vault shrinks
+        assert_eq!(vesting.sync_vault_balance(2_000)?, -2_500);
+        assert_eq!(vesting.vault_balance, TokenAmount::new(2_000));
+        assert_eq!(vesting.unfunded_liability, TokenAmount::new(2_000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_transfers_admin_in_two_steps() -> Result<()> {
+        let admin = Pubkey::new_unique();
+        let nominee = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            admin,
+            ..Default::default()
+        };
+
+        // Nobody can accept before a nomination
+        assert!(vesting.accept_admin(&Pubkey::default()).is_err());
+
+        vesting.propose_admin(nominee);
+        assert_eq!(vesting.admin, admin);
+        assert!(vesting.accept_admin(&admin).is_err());
+
+        assert_eq!(vesting.accept_admin(&nominee)?, admin);
+        assert_eq!(vesting.admin, nominee);
+        assert_eq!(vesting.pending_admin, Pubkey::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_falls_back_to_admin_for_unset_roles() {
+        let admin = Pubkey::new_unique();
+        let wallet_manager = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            admin,
+            roles: VestingRoles {
+                wallet_manager,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(vesting.wallet_manager(), wallet_manager);
+        assert_eq!(vesting.funder(), admin);
+        assert_eq!(vesting.revoker(), admin);
+        assert_eq!(vesting.closer(), admin);
 
-            assert_eq!(vesting.cumulative_vested, TokenAmount::new(vested_tokens));
+        // Unset roles follow the admin when it changes
+        let new_admin = Pubkey::new_unique();
+        vesting.set_admin(new_admin);
+        assert_eq!(vesting.closer(), new_admin);
+        assert_eq!(vesting.wallet_manager(), wallet_manager);
+    }
+
+    #[test]
+    fn it_sets_timelock() -> Result<()> {
+        let admin = Pubkey::new_unique();
+        let vestee = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            admin,
+            ..Default::default()
+        };
+        assert!(vesting.check_not_timelocked().is_ok());
+        assert!(!vesting.is_action_cancel_authorized(&Pubkey::default(), &vestee, 0));
+
+        assert!(vesting.set_timelock(-1, guardian).is_err());
+        assert!(vesting
+            .set_timelock(Vesting::MAX_ACTION_DELAY_SECS + 1, guardian)
+            .is_err());
+
+        vesting.set_timelock(86_400, guardian)?;
+        assert!(vesting.check_not_timelocked().is_err());
+        assert!(vesting.is_action_cancel_authorized(&vestee, &vestee, 0));
+        assert!(vesting.is_action_cancel_authorized(&guardian, &vestee, 0));
+        assert!(vesting.is_action_cancel_authorized(&admin, &vestee, 0));
+        assert!(!vesting.is_action_cancel_authorized(&Pubkey::new_unique(), &vestee, 0));
 
-            // Increment month and year datetime
-            current_year = if current_month == 12 {
-                current_year + 1
-            } else {
-                current_year
-            };
-            current_month = if current_month < 12 {
-                current_month + 1
-            } else {
-                1
-            };
-        }
         Ok(())
     }
 
     #[test]
-    fn it_updates_vested_tokens_daily() -> Result<()> {
+    fn it_freezes_and_unfreezes() -> Result<()> {
+        let admin = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let vestee = Pubkey::new_unique();
         let mut vesting = Vesting {
-            period_type: PeriodType::Daily,
-            total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 6, 10)),
-            total_periods: 100,
-            cliff_periods: 25,
+            admin,
+            guardian,
             ..Default::default()
         };
 
-        let mut current_dt: DateTime<Utc> =
-            DateTime::from_utc(NaiveDateTime::from_timestamp(vesting.start_ts.time, 0), Utc);
+        assert!(vesting.is_freeze_authorized(&admin));
+        assert!(vesting.is_freeze_authorized(&guardian));
+        assert!(!vesting.is_freeze_authorized(&vestee));
 
-        let cliff_dt = current_dt
-            .checked_add_signed(Duration::days(vesting.cliff_periods as i64))
-            .unwrap();
+        assert!(vesting.set_frozen(false).is_err());
+        vesting.set_frozen(true)?;
+        assert!(vesting.set_frozen(true).is_err());
+        assert!(vesting.check_not_frozen().is_err());
 
-        let mut clock;
-        for i in 1..=110 {
-            current_dt = current_dt.checked_add_signed(Duration::days(1)).unwrap();
+        // The vestee can still cancel queued admin actions
+        assert!(vesting.is_action_cancel_authorized(&vestee, &vestee, 0));
+        assert!(vesting.is_action_cancel_authorized(&guardian, &vestee, 0));
 
-            clock = TimeStamp::new_dt(current_dt.date());
-            vesting.update_vested_tokens(clock.time)?;
+        // Neither can the unclaimed tokens be swept
+        vesting.claim_deadline = TimeStamp { time: 10 };
+        assert!(vesting.check_can_sweep(20).is_err());
 
-            // Check that cumulative vested amount is correct
-            let vested_tokens = if current_dt < cliff_dt {
-                0
-            } else if i < 100 {
-                i * 10_000 / 100
-            } else {
-                10_000
-            };
+        vesting.set_frozen(false)?;
+        assert!(vesting.check_not_frozen().is_ok());
+        assert!(vesting.check_can_sweep(20).is_ok());
+        assert!(vesting.is_action_cancel_authorized(&vestee, &vestee, 0));
 
-            assert_eq!(vesting.cumulative_vested, TokenAmount::new(vested_tokens));
-        }
         Ok(())
     }
 
     #[test]
-    fn it_updates_vested_tokens_when_vesting_day_is_eom() -> Result<()> {
-        let clock = TimeStamp::new_dt(Utc.ymd(2022, 2, 28));
+    fn it_changes_vestee_wallet_on_request() -> Result<()> {
+        let vestee_wallet = Pubkey::new_unique();
+        let requested = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            vestee_wallet,
+            ..Default::default()
+        };
+
+        let owner = Pubkey::new_unique();
+        assert!(vesting
+            .request_vestee_wallet_change(vestee_wallet, owner)
+            .is_err());
+        assert_eq!(
+            vesting.request_vestee_wallet_change(requested, owner)?,
+            None
+        );
+        assert_eq!(vesting.vestee_wallet, vestee_wallet);
+        assert_eq!(vesting.pending_vestee_wallet, requested);
+
+        assert!(vesting
+            .approve_vestee_wallet_change(&Pubkey::new_unique(), owner)
+            .is_err());
+        assert_eq!(
+            vesting.approve_vestee_wallet_change(&requested, owner)?,
+            vestee_wallet
+        );
+        assert_eq!(vesting.vestee_wallet, requested);
+        assert_eq!(vesting.vestee_wallet_owner, owner);
+        assert_eq!(vesting.pending_vestee_wallet, Pubkey::default());
+        assert_eq!(vesting.vestee(&[])?, owner);
+
+        // Nothing left to approve
+        assert!(vesting
+            .approve_vestee_wallet_change(&requested, owner)
+            .is_err());
+
+        vesting.immediate_vestee_wallet_change = true;
+        let immediate = Pubkey::new_unique();
+        assert_eq!(
+            vesting.request_vestee_wallet_change(immediate, owner)?,
+            Some(requested)
+        );
+        assert_eq!(vesting.vestee_wallet, immediate);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_checks_withdrawal_destination() -> Result<()> {
+        let vestee_wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            vestee_wallet,
+            mint,
+            ..Default::default()
+        };
+        let other_wallet = Pubkey::new_unique();
+
+        vesting.check_withdrawal_destination(&vestee_wallet)?;
+        assert!(vesting.check_withdrawal_destination(&other_wallet).is_err());
+        vesting.check_escrow_destination(&other_wallet)?;
+        assert!(vesting.can_receive_withdrawals(&mint, &Pubkey::new_unique()));
+        assert!(!vesting.can_receive_withdrawals(&Pubkey::new_unique(), &owner));
+
+        // Once the owner is recorded, a reassigned vestee wallet escrows
+        vesting.vestee_wallet_owner = owner;
+        assert!(vesting.can_receive_withdrawals(&mint, &owner));
+        assert!(!vesting.can_receive_withdrawals(&mint, &Pubkey::new_unique()));
+
+        vesting.set_options(
+            VestingOptions {
+                withdraw_to_owner: true,
+                ..Default::default()
+            },
+            &owner,
+        )?;
+        assert_eq!(vesting.beneficiary_owner, owner);
+
+        vesting.check_withdrawal_destination(&other_wallet)?;
+        assert!(vesting.can_receive_withdrawals(&mint, &owner));
+        assert!(!vesting.can_receive_withdrawals(&Pubkey::new_unique(), &owner));
+        assert!(!vesting.can_receive_withdrawals(&mint, &Pubkey::new_unique()));
+
+        vesting.check_escrow_destination(&vestee_wallet)?;
+        vesting.check_escrow_destination(&get_associated_token_address(&owner, &mint))?;
+        assert!(vesting.check_escrow_destination(&other_wallet).is_err());
+
+        // The beneficiary owner follows the vestee wallet
+        let new_owner = Pubkey::new_unique();
+        vesting.change_vestee_wallet(other_wallet, new_owner)?;
+        assert_eq!(vesting.beneficiary_owner, new_owner);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_records_undelivered_withdrawals_as_claimable() -> Result<()> {
+        let mut vesting = Vesting {
+            cumulative_vested: TokenAmount::new(1_000),
+            vault_balance: TokenAmount::new(1_000),
+            ..Default::default()
+        };
+        assert!(vesting.take_claimable_balance().is_err());
+
+        vesting.record_withdrawal(TokenAmount::new(100), true);
+        vesting.record_withdrawal(TokenAmount::new(300), false);
+        assert_eq!(vesting.vault_balance, TokenAmount::new(600));
+        assert_eq!(vesting.cumulative_withdrawn, TokenAmount::new(400));
+        assert_eq!(vesting.claimable_balance, TokenAmount::new(300));
+
+        // The claimable balance stays in the vault but is not vault balance
+        assert_eq!(vesting.get_sweepable_amount(900), 600);
+        assert_eq!(vesting.sync_vault_balance(900)?, 0);
+
+        assert_eq!(vesting.take_claimable_balance()?, TokenAmount::new(300));
+        assert_eq!(vesting.claimable_balance, TokenAmount::new(0));
 
+        Ok(())
+    }
+
+    #[test]
+    fn it_gets_surplus() {
         let mut vesting = Vesting {
             total_vesting: TokenAmount::new(10_000),
-            cumulative_vested: TokenAmount::new(0),
-            start_ts: TimeStamp::new_dt(Utc.ymd(2022, 1, 31)),
+            cumulative_vested: TokenAmount::new(5_000),
+            cumulative_withdrawn: TokenAmount::new(2_000),
+            vault_balance: TokenAmount::new(7_000),
+            ..Default::default()
+        };
+
+        // Remaining obligation = 10_000 - 2_000
+        assert_eq!(vesting.get_surplus(), 0);
+
+        vesting.vault_balance = TokenAmount::new(8_500);
+        assert_eq!(vesting.get_surplus(), 500);
+
+        vesting.cumulative_withdrawn = TokenAmount::new(10_000);
+        assert_eq!(vesting.get_surplus(), 8_500);
+    }
+
+    fn schedule_params() -> ScheduleParams {
+        ScheduleParams {
+            vesting_amount: TokenAmount::new(10_000),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2022, 1, 1)),
+            cliff_periods: 12,
             total_periods: 48,
-            cliff_periods: 0,
+            period_type: PeriodType::Monthly,
+        }
+    }
+
+    fn schedule_params_error(params: ScheduleParams) -> anchor_lang::error::Error {
+        let clock_ts = TimeStamp::new_dt(Utc.ymd(2022, 6, 1));
+
+        Vesting::default()
+            .set_schedule_params(params, clock_ts.time)
+            .unwrap_err()
+    }
+
+    #[test]
+    fn it_sets_schedule_params() -> Result<()> {
+        let clock_ts = TimeStamp::new_dt(Utc.ymd(2022, 6, 1));
+        let mut vesting = Vesting::default();
+
+        vesting.set_schedule_params(schedule_params(), clock_ts.time)?;
+
+        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
+        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2022, 1, 1)));
+        assert_eq!(vesting.cliff_periods, 12);
+        assert_eq!(vesting.total_periods, 48);
+        assert_eq!(vesting.period_type, PeriodType::Monthly);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_builds_schedule_params_from_u32_period_type() -> Result<()> {
+        let params = ScheduleParams::new(
+            TokenAmount::new(10_000),
+            TimeStamp::new_dt(Utc.ymd(2022, 1, 1)),
+            12,
+            48,
+            2,
+        )?;
+        assert_eq!(params, schedule_params());
+
+        assert!(ScheduleParams::new(
+            TokenAmount::new(10_000),
+            TimeStamp::new_dt(Utc.ymd(2022, 1, 1)),
+            12,
+            48,
+            0,
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_validates_schedule_params() {
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                vesting_amount: TokenAmount::new(0),
+                ..schedule_params()
+            }),
+            TreasuryError::ZeroVestingAmount.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                total_periods: 0,
+                cliff_periods: 0,
+                ..schedule_params()
+            }),
+            TreasuryError::ZeroTotalPeriods.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                cliff_periods: 49,
+                ..schedule_params()
+            }),
+            TreasuryError::CliffExceedsTotalPeriods.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                period_type: PeriodType::Yearly,
+                ..schedule_params()
+            }),
+            TreasuryError::UnsupportedPeriodType.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                start_ts: TimeStamp::new_dt(Utc.ymd(2040, 1, 1)),
+                ..schedule_params()
+            }),
+            TreasuryError::StartTimestampOutOfBounds.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                start_ts: TimeStamp::new(i64::MIN),
+                ..schedule_params()
+            }),
+            TreasuryError::StartTimestampOutOfBounds.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                total_periods: u64::MAX,
+                ..schedule_params()
+            }),
+            TreasuryError::ScheduleEndOverflow.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                total_periods: u64::MAX,
+                period_type: PeriodType::Daily,
+                ..schedule_params()
+            }),
+            TreasuryError::ScheduleEndOverflow.into()
+        );
+
+        assert_eq!(
+            schedule_params_error(ScheduleParams {
+                total_periods: 100_000_000,
+                period_type: PeriodType::Daily,
+                ..schedule_params()
+            }),
+            TreasuryError::ScheduleEndOverflow.into()
+        );
+    }
+
+    #[test]
+    fn it_sets_schedule() -> Result<()> {
+        let mut vesting = Vesting::default();
+
+        vesting.set_schedule(
+            TokenAmount::new(10_000),
+            TimeStamp::new_dt(Utc.ymd(2020, 1, 1)),
+            12,
+            48,
+            1,
+        )?;
+
+        assert_eq!(vesting.total_vesting, TokenAmount::new(10_000));
+        assert_eq!(vesting.start_ts, TimeStamp::new_dt(Utc.ymd(2020, 1, 1)));
+        assert_eq!(vesting.cliff_periods, 12);
+        assert_eq!(vesting.total_periods, 48);
+        assert_eq!(vesting.period_type, PeriodType::Daily);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_invalid_schedule() {
+        let mut vesting = Vesting::default();
+        let start_ts = TimeStamp::new_dt(Utc.ymd(2020, 1, 1));
+
+        // Unsupported period type
+        assert!(vesting
+            .set_schedule(TokenAmount::new(10_000), start_ts, 12, 48, 3)
+            .is_err());
+
+        // More cliff periods than periods in total
+        assert!(vesting
+            .set_schedule(TokenAmount::new(10_000), start_ts, 49, 48, 2)
+            .is_err());
+    }
+
+    #[test]
+    fn it_sets_blackout_windows() -> Result<()> {
+        let mut vesting = Vesting::default();
+
+        let windows = vec![
+            BlackoutWindow::new(TimeStamp::new(100), TimeStamp::new(200)),
+            BlackoutWindow::new(TimeStamp::new(500), TimeStamp::new(600)),
+        ];
+        vesting.set_blackout_windows(&windows)?;
+
+        assert_eq!(vesting.blackout_windows[..2], windows[..]);
+        assert!(vesting.blackout_windows[2..]
+            .iter()
+            .all(|window| *window == BlackoutWindow::default()));
+
+        // Setting a new list replaces the previous one
+        vesting.set_blackout_windows(&windows[1..])?;
+        assert_eq!(vesting.blackout_windows[0], windows[1]);
+        assert_eq!(vesting.blackout_windows[1], BlackoutWindow::default());
+
+        vesting.set_blackout_windows(&[])?;
+        assert_eq!(vesting.blackout_windows, [BlackoutWindow::default(); 8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_invalid_blackout_windows() {
+        let mut vesting = Vesting::default();
+
+        let inverted = vec![BlackoutWindow::new(
+            TimeStamp::new(200),
+            TimeStamp::new(100),
+        )];
+        assert!(vesting.set_blackout_windows(&inverted).is_err());
+
+        let empty = vec![BlackoutWindow::new(
+            TimeStamp::new(100),
+            TimeStamp::new(100),
+        )];
+        assert!(vesting.set_blackout_windows(&empty).is_err());
+
+        let too_many: Vec<_> = (0..=Vesting::MAX_BLACKOUT_WINDOWS as i64)
+            .map(|i| BlackoutWindow::new(TimeStamp::new(i * 100), TimeStamp::new(i * 100 + 50)))
+            .collect();
+        assert!(vesting.set_blackout_windows(&too_many).is_err());
+
+        let too_long = vec![BlackoutWindow::new(
+            TimeStamp::new(100),
+            TimeStamp::new(101 + Vesting::MAX_BLACKOUT_WINDOW_SECS),
+        )];
+        assert!(vesting.set_blackout_windows(&too_long).is_err());
+
+        let overlapping = vec![
+            BlackoutWindow::new(TimeStamp::new(100), TimeStamp::new(200)),
+            BlackoutWindow::new(TimeStamp::new(199), TimeStamp::new(300)),
+        ];
+        assert!(vesting.set_blackout_windows(&overlapping).is_err());
+
+        let unsorted = vec![
+            BlackoutWindow::new(TimeStamp::new(500), TimeStamp::new(600)),
+            BlackoutWindow::new(TimeStamp::new(100), TimeStamp::new(200)),
+        ];
+        assert!(vesting.set_blackout_windows(&unsorted).is_err());
+    }
+
+    #[test]
+    fn it_ends_blackout_windows_before_claim_deadline() -> Result<()> {
+        let mut vesting = Vesting {
+            claim_deadline: TimeStamp::new(1_000),
             ..Default::default()
         };
 
-        vesting.update_vested_tokens(clock.time)?;
+        let reaching_deadline = vec![BlackoutWindow::new(
+            TimeStamp::new(900),
+            TimeStamp::new(1_000),
+        )];
+        assert!(vesting.set_blackout_windows(&reaching_deadline).is_err());
 
-        // Check that cumulative vested amount is correct
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(0));
+        vesting.set_blackout_windows(&[BlackoutWindow::new(
+            TimeStamp::new(900),
+            TimeStamp::new(999),
+        )])?;
 
-        let clock = TimeStamp::new_dt(Utc.ymd(2022, 3, 1));
-        vesting.update_vested_tokens(clock.time)?;
-        assert_eq!(vesting.cumulative_vested, TokenAmount::new(208));
         Ok(())
     }
 
     #[test]
-    fn it_updates_unfunded_liabilities_when_positive() -> Result<()> {
+    fn it_checks_if_in_blackout() -> Result<()> {
+        let mut vesting = Vesting::default();
+        assert!(!vesting.is_in_blackout(0));
+
+        vesting.set_blackout_windows(&[
+            BlackoutWindow::new(TimeStamp::new(100), TimeStamp::new(200)),
+            BlackoutWindow::new(TimeStamp::new(500), TimeStamp::new(600)),
+        ])?;
+
+        assert!(!vesting.is_in_blackout(0));
+        assert!(!vesting.is_in_blackout(99));
+        assert!(vesting.is_in_blackout(100));
+        assert!(vesting.is_in_blackout(199));
+        assert!(!vesting.is_in_blackout(200));
+        assert!(!vesting.is_in_blackout(499));
+        assert!(vesting.is_in_blackout(550));
+        assert!(!vesting.is_in_blackout(600));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_sets_claim_deadline() -> Result<()> {
         let mut vesting = Vesting {
-            cumulative_vested: TokenAmount::new(5_000),
-            cumulative_withdrawn: TokenAmount::new(2500),
-            vault_balance: TokenAmount::new(500),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 1, 1)),
+            total_periods: 48,
+            cliff_periods: 12,
             ..Default::default()
         };
 
-        vesting.update_unfunded_liability()?;
+        assert_eq!(vesting.end_ts()?, TimeStamp::new_dt(Utc.ymd(2024, 1, 1)));
 
-        // Unfunded liability = 5_000 - 2_500 - 500
-        assert_eq!(vesting.unfunded_liability, TokenAmount::new(2_000));
+        assert!(vesting
+            .set_claim_deadline(TimeStamp::new_dt(Utc.ymd(2023, 12, 31)))
+            .is_err());
+
+        vesting.set_claim_deadline(TimeStamp::new_dt(Utc.ymd(2024, 1, 1)))?;
+        assert_eq!(
+            vesting.claim_deadline,
+            TimeStamp::new_dt(Utc.ymd(2024, 1, 1))
+        );
+
+        vesting.set_claim_deadline(TimeStamp::new(0))?;
+        assert_eq!(vesting.claim_deadline, TimeStamp::new(0));
 
         Ok(())
     }
 
     #[test]
-    fn it_does_not_update_unfunded_liabilities_when_none() -> Result<()> {
+    fn it_checks_if_past_claim_deadline() {
+        let mut vesting = Vesting::default();
+        assert!(!vesting.is_past_claim_deadline(i64::MAX));
+
+        vesting.claim_deadline = TimeStamp::new(1_000);
+        assert!(!vesting.is_past_claim_deadline(999));
+        assert!(vesting.is_past_claim_deadline(1_000));
+        assert!(vesting.is_past_claim_deadline(1_001));
+    }
+
+    #[test]
+    fn it_authorizes_anyone_to_withdraw_by_default() {
+        let vesting = Vesting::default();
+        let vestee = Pubkey::new_unique();
+
+        assert!(vesting.is_withdrawal_authorized(&vestee, &vestee, 0));
+        assert!(vesting.is_withdrawal_authorized(&Pubkey::new_unique(), &vestee, 0));
+    }
+
+    #[test]
+    fn it_authorizes_only_beneficiary_to_withdraw() {
         let mut vesting = Vesting {
-            cumulative_vested: TokenAmount::new(5_000),
-            cumulative_withdrawn: TokenAmount::new(5_000),
-            vault_balance: TokenAmount::new(0),
+            beneficiary_only: true,
             ..Default::default()
         };
+        let vestee = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
 
-        vesting.update_unfunded_liability()?;
+        assert!(vesting.is_withdrawal_authorized(&vestee, &vestee, 0));
+        assert!(!vesting.is_withdrawal_authorized(&delegate, &vestee, 0));
+        assert!(!vesting.is_withdrawal_authorized(&Pubkey::default(), &vestee, 0));
 
-        assert_eq!(vesting.unfunded_liability, TokenAmount::new(0));
+        vesting.withdrawal_delegate = delegate;
+        assert!(vesting.is_withdrawal_authorized(&vestee, &vestee, 0));
+        assert!(vesting.is_withdrawal_authorized(&delegate, &vestee, 0));
+        assert!(!vesting.is_withdrawal_authorized(&Pubkey::new_unique(), &vestee, 0));
+    }
+
+    #[test]
+    fn it_authorizes_operators_until_expiry() -> Result<()> {
+        let mut vesting = Vesting {
+            beneficiary_only: true,
+            ..Default::default()
+        };
+        let vestee = Pubkey::new_unique();
+        let custodian = Pubkey::new_unique();
+        let tax_service = Pubkey::new_unique();
+
+        vesting.set_operator(
+            VesteeOperator {
+                key: custodian,
+                permissions: VesteeOperator::WITHDRAW,
+                expires_at: TimeStamp::new(1_000),
+            },
+            0,
+        )?;
+        vesting.set_operator(
+            VesteeOperator {
+                key: tax_service,
+                permissions: VesteeOperator::CANCEL_ADMIN_ACTION,
+                ..Default::default()
+            },
+            0,
+        )?;
+
+        assert!(vesting.is_withdrawal_authorized(&custodian, &vestee, 999));
+        assert!(!vesting.is_withdrawal_authorized(&custodian, &vestee, 1_000));
+        assert!(!vesting.is_withdrawal_authorized(&tax_service, &vestee, 0));
+        assert!(vesting.is_action_cancel_authorized(&tax_service, &vestee, i64::MAX));
+        assert!(!vesting.is_action_cancel_authorized(&custodian, &vestee, 0));
+
+        // Permissions of an existing operator are replaced
+        vesting.set_operator(
+            VesteeOperator {
+                key: custodian,
+                permissions: VesteeOperator::ALL,
+                ..Default::default()
+            },
+            0,
+        )?;
+        assert!(vesting.is_action_cancel_authorized(&custodian, &vestee, 2_000));
+
+        // No permissions removes the operator
+        vesting.set_operator(
+            VesteeOperator {
+                key: custodian,
+                ..Default::default()
+            },
+            0,
+        )?;
+        assert!(!vesting.is_withdrawal_authorized(&custodian, &vestee, 0));
+        assert!(!vesting.is_operator(&Pubkey::default(), 0, 0));
 
         Ok(())
     }
 
     #[test]
-    fn it_does_not_update_unfunded_liabilities_when_overfunded() -> Result<()> {
+    fn it_drops_delegates_when_vestee_wallet_changes() -> Result<()> {
+        let operator = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let mut vesting = Vesting {
+            beneficiary_only: true,
+            withdrawal_delegate: delegate,
+            ..Default::default()
+        };
+        vesting.set_operator(
+            VesteeOperator {
+                key: operator,
+                permissions: VesteeOperator::ALL,
+                ..Default::default()
+            },
+            0,
+        )?;
+
+        let vestee = Pubkey::new_unique();
+        vesting.change_vestee_wallet(Pubkey::new_unique(), vestee)?;
+
+        assert_eq!(vesting.withdrawal_delegate, Pubkey::default());
+        assert_eq!(
+            vesting.operators,
+            [VesteeOperator::default(); Vesting::MAX_OPERATORS]
+        );
+        assert!(!vesting.is_withdrawal_authorized(&operator, &vestee, 0));
+        assert!(!vesting.is_withdrawal_authorized(&delegate, &vestee, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_validates_operators() -> Result<()> {
+        let mut vesting = Vesting::default();
+        let operator = |expires_at: i64| VesteeOperator {
+            key: Pubkey::new_unique(),
+            permissions: VesteeOperator::WITHDRAW,
+            expires_at: TimeStamp::new(expires_at),
+        };
+
+        assert!(vesting
+            .set_operator(
+                VesteeOperator {
+                    key: Pubkey::default(),
+                    ..operator(0)
+                },
+                0
+            )
+            .is_err());
+        assert!(vesting
+            .set_operator(
+                VesteeOperator {
+                    permissions: 1 << 7,
+                    ..operator(0)
+                },
+                0
+            )
+            .is_err());
+        assert!(vesting.set_operator(operator(100), 100).is_err());
+        assert!(vesting
+            .set_operator(
+                VesteeOperator {
+                    permissions: 0,
+                    ..operator(0)
+                },
+                0
+            )
+            .is_err());
+
+        vesting.set_operator(operator(100), 0)?;
+        vesting.set_operator(operator(0), 0)?;
+        vesting.set_operator(operator(0), 0)?;
+        assert!(vesting.set_operator(operator(0), 0).is_err());
+
+        // The slot of the expired operator is reused
+        let late = operator(0);
+        vesting.set_operator(late, 100)?;
+        assert_eq!(vesting.operators[0], late);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_invalid_rate_limit() {
+        let mut vesting = Vesting::default();
+
+        assert!(vesting
+            .set_rate_limit(RateLimit::new(-1, TokenAmount::new(100)))
+            .is_err());
+        assert!(vesting
+            .set_rate_limit(RateLimit::new(3_600, TokenAmount::new(0)))
+            .is_err());
+        assert!(vesting.set_rate_limit(RateLimit::default()).is_ok());
+    }
+
+    #[test]
+    fn it_does_not_limit_withdrawals_without_rate_limit() -> Result<()> {
+        let mut vesting = Vesting::default();
+
+        vesting.apply_rate_limit(100, u64::MAX)?;
+        assert_eq!(vesting.rate_limit_buckets, [TokenAmount::new(0); 9]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_applies_rate_limit_within_window() -> Result<()> {
+        let mut vesting = Vesting::default();
+        // Buckets of 125 seconds
+        vesting.set_rate_limit(RateLimit::new(1_000, TokenAmount::new(500)))?;
+        assert_eq!(vesting.rate_limit.bucket_secs(), 125);
+
+        vesting.apply_rate_limit(10_000, 200)?;
+        assert_eq!(vesting.rate_limit_head_bucket, 80);
+        assert_eq!(vesting.get_rate_limit_withdrawn(10_000), 200);
+
+        vesting.apply_rate_limit(10_500, 300)?;
+        assert_eq!(vesting.rate_limit_head_bucket, 84);
+        assert_eq!(vesting.get_rate_limit_withdrawn(10_500), 500);
+
+        // The window is exhausted, state stays as it was
+        assert!(vesting.apply_rate_limit(10_999, 1).is_err());
+        assert_eq!(vesting.get_rate_limit_withdrawn(10_999), 500);
+
+        // The first withdrawal stops counting once its whole bucket is older
+        // than the window, the second one still counts
+        assert!(vesting.apply_rate_limit(11_124, 1).is_err());
+        vesting.apply_rate_limit(11_125, 200)?;
+        assert_eq!(vesting.get_rate_limit_withdrawn(11_125), 500);
+        assert!(vesting.apply_rate_limit(11_499, 1).is_err());
+
+        vesting.apply_rate_limit(11_625, 300)?;
+        assert_eq!(vesting.get_rate_limit_withdrawn(11_625), 500);
+
+        // Long after, all the buckets are stale
+        vesting.apply_rate_limit(100_000, 500)?;
+        assert_eq!(vesting.get_rate_limit_withdrawn(100_000), 500);
+        assert_eq!(
+            vesting
+                .rate_limit_buckets
+                .iter()
+                .filter(|bucket| bucket.amount != 0)
+                .count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_does_not_reset_rate_limit_window_on_first_withdrawal() -> Result<()> {
+        let mut vesting = Vesting::default();
+        // Seven days, split into buckets of 21 hours
+        let window_secs = 7 * 86_400;
+        vesting.set_rate_limit(RateLimit::new(window_secs, TokenAmount::new(100)))?;
+
+        let t0 = 100 * 86_400;
+        vesting.apply_rate_limit(t0, 1)?;
+        vesting.apply_rate_limit(t0 + 6 * 86_400 + 79_000, 99)?;
+
+        // A tumbling window would start over here and allow another 100
+        assert!(vesting.apply_rate_limit(t0 + window_secs, 100).is_err());
+        assert!(vesting.apply_rate_limit(t0 + window_secs, 1).is_err());
+        assert_eq!(vesting.get_rate_limit_allowance(t0 + window_secs), 0);
+
+        // Only the first withdrawal is out of the window one day later
+        vesting.apply_rate_limit(t0 + window_secs + 86_400, 1)?;
+        assert!(vesting
+            .apply_rate_limit(t0 + window_secs + 86_400, 1)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_computes_max_withdrawable() -> Result<()> {
         let mut vesting = Vesting {
             cumulative_vested: TokenAmount::new(5_000),
-            cumulative_withdrawn: TokenAmount::new(0),
+            cumulative_withdrawn: TokenAmount::new(1_000),
             vault_balance: TokenAmount::new(10_000),
             ..Default::default()
         };
 
-        vesting.update_unfunded_liability()?;
+        // Capped by the liability
+        assert_eq!(vesting.get_max_withdrawable(0), TokenAmount::new(4_000));
 
-        assert_eq!(vesting.unfunded_liability, TokenAmount::new(0));
+        // Capped by the vault balance
+        vesting.vault_balance = TokenAmount::new(1_500);
+        assert_eq!(vesting.get_max_withdrawable(0), TokenAmount::new(1_500));
+
+        // Capped by the rate limit
+        vesting.set_rate_limit(RateLimit::new(1_000, TokenAmount::new(800)))?;
+        assert_eq!(vesting.get_max_withdrawable(0), TokenAmount::new(800));
+
+        vesting.apply_rate_limit(0, 300)?;
+        assert_eq!(vesting.get_max_withdrawable(999), TokenAmount::new(500));
+        // The bucket of the withdrawal counts until all of it left the window
+        assert_eq!(vesting.get_max_withdrawable(1_124), TokenAmount::new(500));
+        assert_eq!(vesting.get_max_withdrawable(1_125), TokenAmount::new(800));
 
         Ok(())
     }
@@ -678,4 +2646,101 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn it_derives_space_from_fields() {
+        let vesting = Vesting {
+            blackout_windows: [BlackoutWindow {
+                start: TimeStamp { time: i64::MAX },
+                end: TimeStamp { time: i64::MAX },
+            }; Vesting::MAX_BLACKOUT_WINDOWS],
+            start_ts: TimeStamp { time: i64::MIN },
+            reserved: [u8::MAX; 18],
+            rate_limit_head_bucket: i64::MAX,
+            rate_limit_buckets: [TokenAmount::new(u64::MAX); 9],
+            claimable_balance: TokenAmount::new(u64::MAX),
+            frozen: true,
+            registered: true,
+            vestee_wallet_owner: Pubkey::new_unique(),
+            beneficiary_owner: Pubkey::new_unique(),
+            operators: [VesteeOperator {
+                key: Pubkey::new_unique(),
+                permissions: u8::MAX,
+                expires_at: TimeStamp { time: i64::MAX },
+            }; Vesting::MAX_OPERATORS],
+            pending_vestee_wallet: Pubkey::new_unique(),
+            immediate_vestee_wallet_change: true,
+            guardian: Pubkey::new_unique(),
+            action_delay_secs: i64::MAX,
+            pending_admin: Pubkey::new_unique(),
+            roles: VestingRoles {
+                funder: Pubkey::new_unique(),
+                wallet_manager: Pubkey::new_unique(),
+                revoker: Pubkey::new_unique(),
+                closer: Pubkey::new_unique(),
+            },
+            ..Default::default()
+        };
+
+        let mut data = Vec::new();
+        vesting.try_serialize(&mut data).unwrap();
+
+        assert_eq!(data.len(), Vesting::space());
+        assert_eq!(Vesting::space(), 915);
+    }
+
+    #[test]
+    fn it_migrates_baseline_layout() -> Result<()> {
+        let vesting = Vesting {
+            admin: Pubkey::new_unique(),
+            vestee_wallet: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            total_vesting: TokenAmount::new(10_000),
+            cumulative_vested: TokenAmount::new(2_500),
+            start_ts: TimeStamp::new_dt(Utc.ymd(2020, 1, 1)),
+            total_periods: 48,
+            cliff_periods: 12,
+            period_type: PeriodType::Daily,
+            ..Default::default()
+        };
+
+        // The baseline layout ended with the period type and its accounts
+        // were allocated with 28 extra zeroed bytes.
+        let mut data = Vec::new();
+        vesting.try_serialize(&mut data).unwrap();
+        data.truncate(201);
+        data.resize(229, 0);
+
+        let migrated = Vesting::from_legacy_data(&data)?;
+
+        assert_eq!(migrated.admin, vesting.admin);
+        assert_eq!(migrated.vestee_wallet, vesting.vestee_wallet);
+        assert_eq!(migrated.mint, vesting.mint);
+        assert_eq!(migrated.vault, vesting.vault);
+        assert_eq!(migrated.total_vesting, vesting.total_vesting);
+        assert_eq!(migrated.cumulative_vested, vesting.cumulative_vested);
+        assert_eq!(migrated.start_ts, vesting.start_ts);
+        assert_eq!(migrated.total_periods, 48);
+        assert_eq!(migrated.cliff_periods, 12);
+        assert_eq!(migrated.period_type, PeriodType::Daily);
+        assert_eq!(migrated.claim_deadline, TimeStamp::default());
+        assert!(!migrated.rate_limit.is_enabled());
+        assert_eq!(migrated.version, Vesting::CURRENT_VERSION);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_does_not_migrate_current_version() {
+        let vesting = Vesting {
+            version: Vesting::CURRENT_VERSION,
+            ..Default::default()
+        };
+
+        let mut data = Vec::new();
+        vesting.try_serialize(&mut data).unwrap();
+
+        assert!(Vesting::from_legacy_data(&data).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 pub use crate::endpoints;
 pub use crate::err::{self, TreasuryError};
+pub use crate::events::*;
 pub use crate::models::*;
 pub use crate::periods;
 pub use crate::time::{self, *};
@@ -57,6 +57,33 @@ pub fn shift_months<D: Datelike>(date: D, months: i32) -> D {
     }
 }
 
+/// Shift a date by the given number of months like [`shift_months`], but
+/// returns None instead of panicking or overflowing if the resulting date is
+/// out of the supported range.
+pub fn checked_shift_months<D: Datelike>(date: D, months: i32) -> Option<D> {
+    let total_months = (date.month() as i32).checked_add(months)?;
+    let mut year = date.year().checked_add(total_months / 12)?;
+    let mut month = total_months % 12;
+
+    if month < 1 {
+        year = year.checked_sub(1)?;
+        month += 12;
+    }
+
+    let day = normalise_day(year, month as u32, date.day());
+
+    if day <= 28 {
+        date.with_day(day)?
+            .with_month(month as u32)?
+            .with_year(year)
+    } else {
+        date.with_day(1)?
+            .with_month(month as u32)?
+            .with_year(year)?
+            .with_day(day)
+    }
+}
+
 /// Shift a date by the given number of years.
 /// Ambiguous month-ends are shifted backwards as necessary.
 pub fn shift_years<D: Datelike>(date: D, years: i32) -> D {
@@ -116,6 +143,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_checked_shift_months() {
+        let base = NaiveDate::from_ymd(2020, 1, 31);
+
+        assert_eq!(
+            checked_shift_months(base, 13),
+            Some(NaiveDate::from_ymd(2021, 2, 28))
+        );
+        assert_eq!(
+            checked_shift_months(base, -1),
+            Some(NaiveDate::from_ymd(2019, 12, 31))
+        );
+        assert_eq!(checked_shift_months(base, i32::MAX), None);
+        assert_eq!(checked_shift_months(base, 12 * 300_000), None);
+    }
+
     #[test]
     fn test_shift_months() {
         let base = NaiveDate::from_ymd(2020, 1, 31);
@@ -0,0 +1,206 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "@project-serum/anchor";
+import { expect } from "chai";
+import { createAccount } from "@solana/spl-token";
+import { errLogs, payer, provider, sleep } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("queue_admin_action, cancel_admin_action and execute_admin_action", () => {
+    const actionDelaySecs = 2;
+    let adminKeypair: Keypair;
+    let guardianKeypair: Keypair;
+    let vesting: Vesting;
+
+    beforeEach("create vesting account with timelock", async () => {
+      adminKeypair = Keypair.generate();
+      guardianKeypair = Keypair.generate();
+      vesting = await Vesting.init({
+        adminKeypair,
+        actionDelaySecs,
+        guardian: guardianKeypair.publicKey,
+      });
+    });
+
+    const newVesteeWallet = () =>
+      createAccount(
+        provider.connection,
+        payer,
+        vesting.mint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+    it("fails to change the vestee wallet immediately", async () => {
+      const logs = await errLogs(
+        vesting.changeVesteeWallet({
+          adminKeypair,
+          vesteeWalletNew: await newVesteeWallet(),
+        })
+      );
+
+      expect(logs).to.contain("AdminActionTimelocked");
+    });
+
+    it("fails to queue without the power of the action", async () => {
+      const logs = await errLogs(
+        vesting.queueAdminAction(guardianKeypair, {
+          proposeAdmin: { pendingAdmin: guardianKeypair.publicKey },
+        })
+      );
+
+      expect(logs).to.contain(
+        "Signer does not hold the power to queue this admin action"
+      );
+    });
+
+    it("executes a wallet change once the delay passed", async () => {
+      const vesteeWalletNew = await newVesteeWallet();
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        changeVesteeWallet: { vesteeWallet: vesteeWalletNew },
+      });
+
+      const logs = await errLogs(
+        vesting.executeAdminAction(queuedAction, [vesteeWalletNew])
+      );
+      expect(logs).to.contain("The delay of the queued action has not passed yet");
+
+      await sleep((actionDelaySecs + 1) * 1000);
+
+      // anyone can execute
+      await vesting.executeAdminAction(queuedAction, [vesteeWalletNew]);
+
+      expect((await vesting.fetch()).vesteeWallet).to.deep.eq(vesteeWalletNew);
+      expect(await provider.connection.getAccountInfo(queuedAction)).to.be
+        .null;
+    });
+
+    it("fails to execute once the queuer lost the power", async () => {
+      const vesteeWalletNew = await newVesteeWallet();
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        changeVesteeWallet: { vesteeWallet: vesteeWalletNew },
+      });
+      const setRoles = await vesting.queueAdminAction(adminKeypair, {
+        setRoles: {
+          roles: Vesting.rolesFrom({
+            walletManager: Keypair.generate().publicKey,
+          }),
+        },
+      });
+      await sleep((actionDelaySecs + 1) * 1000);
+      await vesting.executeAdminAction(setRoles);
+
+      const logs = await errLogs(
+        vesting.executeAdminAction(queuedAction, [vesteeWalletNew])
+      );
+
+      expect(logs).to.contain(
+        "The key which queued the action no longer holds its power"
+      );
+    });
+
+    it("fails to set the roles immediately", async () => {
+      const logs = await errLogs(
+        vesting.setRoles(adminKeypair, {
+          walletManager: Keypair.generate().publicKey,
+        })
+      );
+
+      expect(logs).to.contain("AdminActionTimelocked");
+    });
+
+    it("changes the roles through the queue", async () => {
+      const walletManager = Keypair.generate().publicKey;
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        setRoles: { roles: Vesting.rolesFrom({ walletManager }) },
+      });
+      await sleep((actionDelaySecs + 1) * 1000);
+      await vesting.executeAdminAction(queuedAction);
+
+      expect((await vesting.fetch()).roles.walletManager).to.deep.eq(
+        walletManager
+      );
+    });
+
+    it("fails to set the timelock directly once there is one", async () => {
+      const logs = await errLogs(vesting.setTimelock(adminKeypair, 0));
+
+      expect(logs).to.contain("AdminActionTimelocked");
+    });
+
+    it("lets the guardian cancel a queued action", async () => {
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        proposeAdmin: { pendingAdmin: Keypair.generate().publicKey },
+      });
+
+      const logs = await errLogs(
+        vesting.cancelAdminAction(Keypair.generate(), queuedAction)
+      );
+      expect(logs).to.contain(
+        "Only the vestee, their operator, the guardian or the admin can cancel a queued action"
+      );
+
+      await vesting.cancelAdminAction(guardianKeypair, queuedAction);
+
+      expect(await provider.connection.getAccountInfo(queuedAction)).to.be
+        .null;
+      expect((await vesting.fetch()).pendingAdmin).to.deep.eq(
+        PublicKey.default
+      );
+    });
+
+    it("lets the vestee cancel a queued action", async () => {
+      // the vestee wallet is owned by the provider wallet
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        changeVesteeWallet: { vesteeWallet: await newVesteeWallet() },
+      });
+
+      await vesting.cancelAdminAction(payer, queuedAction);
+
+      expect(await provider.connection.getAccountInfo(queuedAction)).to.be
+        .null;
+    });
+
+    it("changes the timelock through the queue", async () => {
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        setTimelock: { actionDelaySecs: new BN(0), guardian: PublicKey.default },
+      });
+      await sleep((actionDelaySecs + 1) * 1000);
+      await vesting.executeAdminAction(queuedAction);
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.actionDelaySecs.toNumber()).to.eq(0);
+      expect(vestingInfo.guardian).to.deep.eq(PublicKey.default);
+
+      // without a timelock the actions take effect immediately
+      await vesting.changeVesteeWallet({
+        adminKeypair,
+        vesteeWalletNew: await newVesteeWallet(),
+      });
+    });
+
+    it("sets the initial timelock of a schedule without one", async () => {
+      const other = await Vesting.init({ adminKeypair });
+
+      const logs = await errLogs(
+        other.setTimelock(Keypair.generate(), actionDelaySecs)
+      );
+      expect(logs).to.contain("Vesting admin does not match the provided signer");
+
+      await other.setTimelock(
+        adminKeypair,
+        actionDelaySecs,
+        guardianKeypair.publicKey
+      );
+
+      const vestingInfo = await other.fetch();
+      expect(vestingInfo.actionDelaySecs.toNumber()).to.eq(actionDelaySecs);
+      expect(vestingInfo.guardian).to.deep.eq(guardianKeypair.publicKey);
+
+      // from now on the actions must be queued
+      await other.queueAdminAction(adminKeypair, {
+        proposeAdmin: { pendingAdmin: Keypair.generate().publicKey },
+      });
+    });
+  });
+}
@@ -111,7 +111,7 @@ export function test() {
       );
 
       expect(logs).to.contain(
-        "Vesting admin does not match the provided signer"
+        "Vesting wallet manager does not match the provided signer"
       );
     });
 
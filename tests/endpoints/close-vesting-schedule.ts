@@ -1,6 +1,6 @@
 import { Keypair, PublicKey } from "@solana/web3.js";
 import { expect } from "chai";
-import { createMint, createAccount, mintTo } from "@solana/spl-token";
+import { createMint, createAccount, getAccount, mintTo } from "@solana/spl-token";
 import { errLogs, provider, payer, getErr } from "../helpers";
 import { Vesting } from "../vesting";
 
@@ -68,7 +68,7 @@ export function test() {
         vesting.closeVestingSchedule({ adminKeypair: fakeAdminKeypair })
       );
 
-      expect(logs).to.contain("Admin does not own this vesting account");
+      expect(logs).to.contain("Vesting closer does not match the provided signer");
     });
 
     it("fails if not fully vested", async () => {
@@ -124,5 +124,43 @@ export function test() {
 
       expect(logs).to.contain("Account does not exist");
     });
+
+    it("sweeps the vault to the recovery wallet and closes it", async () => {
+      await vesting.updateVestedTokens();
+      const vestingInfo = await vesting.fetch();
+      const vestedAmount = vestingInfo.cumulativeVested.amount.toNumber();
+
+      // fund more than vested such that tokens are left in the vault
+      await vesting.fundVestingVault(
+        { walletAuthority, fundingWallet },
+        vestedAmount + 15
+      );
+      await vesting.withdrawVestedTokens({ vesteeWallet }, vestedAmount);
+
+      const recoveryWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        Keypair.generate().publicKey
+      );
+      const rentReceiver = Keypair.generate().publicKey;
+      const vault = await vesting.vestingVault();
+      const vaultLamports = await provider.connection.getBalance(vault);
+      const vestingLamports = await provider.connection.getBalance(vesting.id);
+
+      await vesting.closeVestingSchedule({
+        adminKeypair,
+        recoveryWallet,
+        rentReceiver,
+      });
+
+      const recoveryInfo = await getAccount(provider.connection, recoveryWallet);
+      expect(Number(recoveryInfo.amount)).to.eq(15);
+      // the rent of both the vesting account and the vault
+      expect(await provider.connection.getBalance(rentReceiver)).to.eq(
+        vaultLamports + vestingLamports
+      );
+      expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    });
   });
 }
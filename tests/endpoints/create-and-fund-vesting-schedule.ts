@@ -0,0 +1,133 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createMint, createAccount, mintTo } from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("create_and_fund_vesting_schedule", () => {
+    const walletAuthority = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let fundingWallet: PublicKey;
+
+    beforeEach("create vesting mint", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee and funding wallets", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey
+      );
+
+      fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+    });
+
+    it("fails if the funding exceeds the total vesting", async () => {
+      const logs = await errLogs(
+        Vesting.initAndFund(
+          {
+            vesteeWallet,
+            mint: vestingMint,
+            walletAuthority,
+            fundingWallet,
+            vestingAmount: 10_000,
+          },
+          10_001
+        )
+      );
+
+      expect(logs).to.contain(
+        "The initial funding amount cannot exceed the total vesting amount"
+      );
+    });
+
+    it("fails if funding wallet mint isn't equal to vesting mint", async () => {
+      const fakeMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+      const fakeWallet = await createAccount(
+        provider.connection,
+        payer,
+        fakeMint,
+        walletAuthority.publicKey
+      );
+
+      const logs = await errLogs(
+        Vesting.initAndFund(
+          {
+            vesteeWallet,
+            mint: vestingMint,
+            walletAuthority,
+            fundingWallet: fakeWallet,
+          },
+          1_000
+        )
+      );
+
+      expect(logs).to.contain("Funding wallet must be of correct mint");
+    });
+
+    it("matches the two step flow", async () => {
+      const vesting = await Vesting.initAndFund(
+        {
+          vesteeWallet,
+          mint: vestingMint,
+          walletAuthority,
+          fundingWallet,
+          vestingAmount: 10_000,
+        },
+        4_000
+      );
+
+      const twoStep = await Vesting.init({
+        vesteeWallet,
+        mint: vestingMint,
+        vestingAmount: 10_000,
+      });
+      await twoStep.fundVestingVault({ walletAuthority, fundingWallet }, 4_000);
+
+      const vestingInfo = await vesting.fetch();
+      const twoStepInfo = await twoStep.fetch();
+
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(4_000);
+      expect(vestingInfo.vaultBalance).to.deep.eq(twoStepInfo.vaultBalance);
+      expect(vestingInfo.unfundedLiability).to.deep.eq(
+        twoStepInfo.unfundedLiability
+      );
+      expect(vestingInfo.totalVesting).to.deep.eq(twoStepInfo.totalVesting);
+      expect(vestingInfo.cumulativeVested).to.deep.eq(
+        twoStepInfo.cumulativeVested
+      );
+
+      const vaultInfo = await vesting.vestingVaultInfo();
+      expect(Number(vaultInfo.amount)).to.eq(4_000);
+    });
+  });
+}
@@ -0,0 +1,86 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createMint, createAccount } from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("create_deterministic_vesting_schedule", () => {
+    const adminKeypair = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+
+    beforeEach("create vesting mint", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee wallet", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey
+      );
+    });
+
+    it("fails if the same grant is created twice", async () => {
+      await Vesting.initDeterministic({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        grantId: 7,
+      });
+
+      const logs = await errLogs(
+        Vesting.initDeterministic({
+          adminKeypair,
+          vesteeWallet,
+          mint: vestingMint,
+          grantId: 7,
+        })
+      );
+
+      expect(logs).to.contain("already in use");
+    });
+
+    it("works", async () => {
+      const vesting = await Vesting.initDeterministic({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        grantId: 1,
+      });
+
+      // The address can be derived offline from the grant
+      expect(vesting.id).to.deep.eq(
+        Vesting.grantFrom(adminKeypair.publicKey, vestingMint, vesteeWallet, 1)
+      );
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.totalVesting.amount.toNumber()).to.eq(10_000);
+      expect(vestingInfo.startTs.time.toNumber()).to.eq(1577836801);
+      expect(vestingInfo.cliffPeriods.toNumber()).to.eq(12);
+      expect(vestingInfo.totalPeriods.toNumber()).to.eq(48);
+      expect(vestingInfo.periodType).to.deep.eq({ monthly: {} });
+      expect(vestingInfo.admin).to.deep.eq(adminKeypair.publicKey);
+      expect(vestingInfo.vesteeWallet).to.deep.eq(vesteeWallet);
+      expect(vestingInfo.mint).to.deep.eq(vestingMint);
+      expect(vestingInfo.vault).to.deep.eq(await vesting.vestingVault());
+
+      // A different grant id yields a different vesting account
+      const vesting2 = await Vesting.initDeterministic({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        grantId: 2,
+      });
+      expect(vesting2.id).to.not.deep.eq(vesting.id);
+    });
+  });
+}
@@ -0,0 +1,269 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createMint, createAccount, mintTo } from "@solana/spl-token";
+import { errLogs, provider, payer, vesting as program } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("create_registry_page", () => {
+    let adminKeypair: Keypair;
+    let vesteeKeypair: Keypair;
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+
+    beforeEach("create vesting mint", async () => {
+      adminKeypair = Keypair.generate();
+      vesteeKeypair = Keypair.generate();
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee wallet", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        vesteeKeypair.publicKey
+      );
+    });
+
+    it("fails if the page already exists", async () => {
+      await Vesting.createRegistryPage("admin", adminKeypair.publicKey, 0);
+
+      const logs = await errLogs(
+        Vesting.createRegistryPage("admin", adminKeypair.publicKey, 0)
+      );
+
+      expect(logs).to.contain("already in use");
+    });
+
+    it("works", async () => {
+      const registry = await Vesting.createRegistryPage(
+        "vestee",
+        vesteeKeypair.publicKey,
+        3
+      );
+
+      const info = await program.account.vestingRegistry.fetch(registry);
+      expect(info.kind).to.deep.eq({ vestee: {} });
+      expect(info.owner).to.deep.eq(vesteeKeypair.publicKey);
+      expect(info.page).to.eq(3);
+      expect(info.schedules).to.be.empty;
+    });
+
+    it("lists created schedules and unlists closed ones", async () => {
+      const vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        // in the past such that the schedule can be closed right away
+        startTs: 1262304001,
+        vestingAmount: 100,
+      });
+      const adminRegistry = Vesting.registryFrom(
+        "admin",
+        adminKeypair.publicKey,
+        0
+      );
+      const vesteeRegistry = Vesting.registryFrom(
+        "vestee",
+        vesteeKeypair.publicKey,
+        0
+      );
+
+      let adminInfo = await program.account.vestingRegistry.fetch(
+        adminRegistry
+      );
+      let vesteeInfo = await program.account.vestingRegistry.fetch(
+        vesteeRegistry
+      );
+      expect(adminInfo.schedules).to.deep.eq([vesting.id]);
+      expect(vesteeInfo.schedules).to.deep.eq([vesting.id]);
+      expect((await vesting.fetch()).registered).to.be.true;
+
+      const walletAuthority = Keypair.generate();
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        100
+      );
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 100);
+      await vesting.closeVestingSchedule({ adminKeypair });
+
+      adminInfo = await program.account.vestingRegistry.fetch(adminRegistry);
+      vesteeInfo = await program.account.vestingRegistry.fetch(
+        vesteeRegistry
+      );
+      expect(adminInfo.schedules).to.be.empty;
+      expect(vesteeInfo.schedules).to.be.empty;
+    });
+
+    it("fails if the admin registry doesn't list the schedule", async () => {
+      const otherAdmin = Keypair.generate();
+      const vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+      });
+      const otherRegistry = await Vesting.createRegistryPage(
+        "admin",
+        otherAdmin.publicKey,
+        0
+      );
+
+      const logs = await errLogs(
+        program.methods
+          .closeVestingSchedule()
+          .accounts({
+            admin: adminKeypair.publicKey,
+            vesting: vesting.id,
+            vestingVault: await vesting.vestingVault(),
+            vestingSigner: await vesting.signerPda(),
+            recoveryWallet: vesteeWallet,
+            rentReceiver: payer.publicKey,
+          })
+          .remainingAccounts(
+            [
+              otherRegistry,
+              Vesting.registryFrom("vestee", vesteeKeypair.publicKey, 0),
+            ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+          )
+          .signers([adminKeypair])
+          .rpc()
+      );
+
+      expect(logs).to.contain(
+        "Vesting account is not listed in the provided registry page"
+      );
+    });
+
+    it("fails to close a listed schedule without its pages", async () => {
+      const vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        startTs: 1262304001,
+        vestingAmount: 100,
+      });
+      await fundAndWithdraw(vesting);
+
+      const logs = await errLogs(
+        program.methods
+          .closeVestingSchedule()
+          .accounts({
+            closer: adminKeypair.publicKey,
+            vesting: vesting.id,
+            vestingVault: await vesting.vestingVault(),
+            vestingSigner: await vesting.signerPda(),
+            recoveryWallet: vesteeWallet,
+            rentReceiver: payer.publicKey,
+          })
+          .signers([adminKeypair])
+          .rpc()
+      );
+
+      expect(logs).to.contain(
+        "The registry pages which list the vesting account must be passed as remaining accounts"
+      );
+    });
+
+    it("creates and closes schedules which are not listed", async () => {
+      // like the schedules created before the registries were introduced
+      const vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        startTs: 1262304001,
+        vestingAmount: 100,
+        skipRegistry: true,
+      });
+      expect((await vesting.fetch()).registered).to.be.false;
+      expect(
+        await program.account.vestingRegistry.fetchNullable(
+          Vesting.registryFrom("admin", adminKeypair.publicKey, 0)
+        )
+      ).to.be.null;
+
+      await fundAndWithdraw(vesting);
+      await vesting.closeVestingSchedule({ adminKeypair });
+
+      expect(await provider.connection.getAccountInfo(vesting.id)).to.be.null;
+    });
+
+    it("moves the vestee listing to the owner of the new wallet", async () => {
+      const vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        startTs: 1262304001,
+        vestingAmount: 100,
+      });
+      const newOwner = Keypair.generate();
+      const vesteeWalletNew = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        newOwner.publicKey
+      );
+
+      await vesting.changeVesteeWallet({ adminKeypair, vesteeWalletNew });
+
+      const oldInfo = await program.account.vestingRegistry.fetch(
+        Vesting.registryFrom("vestee", vesteeKeypair.publicKey, 0)
+      );
+      const newInfo = await program.account.vestingRegistry.fetch(
+        Vesting.registryFrom("vestee", newOwner.publicKey, 0)
+      );
+      expect(oldInfo.schedules).to.be.empty;
+      expect(newInfo.schedules).to.deep.eq([vesting.id]);
+
+      // the schedule closes with the pages it was moved to
+      vesteeWallet = vesteeWalletNew;
+      await fundAndWithdraw(vesting);
+      await vesting.closeVestingSchedule({ adminKeypair });
+
+      expect(
+        (
+          await program.account.vestingRegistry.fetch(
+            Vesting.registryFrom("vestee", newOwner.publicKey, 0)
+          )
+        ).schedules
+      ).to.be.empty;
+    });
+
+    async function fundAndWithdraw(vesting: Vesting) {
+      const walletAuthority = Keypair.generate();
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        100
+      );
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 100);
+    }
+  });
+}
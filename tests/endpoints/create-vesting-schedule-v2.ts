@@ -0,0 +1,90 @@
+import { PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createMint, createAccount } from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("create_vesting_schedule_v2", () => {
+    const now = Math.floor(Date.now() / 1000);
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+
+    beforeEach("create vesting mint and vestee wallet", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey
+      );
+    });
+
+    const cases: [string, object, string][] = [
+      ["the vesting amount is zero", { vestingAmount: 0 }, "ZeroVestingAmount"],
+      [
+        "the total periods are zero",
+        { totalPeriods: 0, cliffPeriods: 0 },
+        "ZeroTotalPeriods",
+      ],
+      [
+        "the cliff exceeds the total periods",
+        { cliffPeriods: 49 },
+        "CliffExceedsTotalPeriods",
+      ],
+      ["the period type is yearly", { periodType: 5 }, "UnsupportedPeriodType"],
+      [
+        "the start is too far in the past",
+        { startTs: now - 11 * 365 * 86_400 },
+        "StartTimestampOutOfBounds",
+      ],
+      [
+        "the end date overflows",
+        { totalPeriods: 4_000_000_000 },
+        "ScheduleEndOverflow",
+      ],
+    ];
+
+    cases.forEach(([name, args, error]) => {
+      it(`fails if ${name}`, async () => {
+        const logs = await errLogs(
+          Vesting.init({
+            vesteeWallet,
+            mint: vestingMint,
+            startTs: now,
+            v2: true,
+            ...args,
+          })
+        );
+
+        expect(logs).to.contain(error);
+      });
+    });
+
+    it("works", async () => {
+      const vesting = await Vesting.init({
+        vesteeWallet,
+        mint: vestingMint,
+        startTs: now,
+        periodType: 1,
+        v2: true,
+      });
+
+      const vestingInfo = await vesting.fetch();
+
+      expect(vestingInfo.totalVesting.amount.toNumber()).to.eq(10_000);
+      expect(vestingInfo.startTs.time.toNumber()).to.eq(now);
+      expect(vestingInfo.cliffPeriods.toNumber()).to.eq(12);
+      expect(vestingInfo.totalPeriods.toNumber()).to.eq(48);
+      expect(vestingInfo.periodType).to.deep.eq({ daily: {} });
+      expect(vestingInfo.vesteeWallet).to.deep.eq(vesteeWallet);
+    });
+  });
+}
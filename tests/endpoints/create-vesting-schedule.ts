@@ -1,6 +1,12 @@
 import { Keypair, PublicKey } from "@solana/web3.js";
 import { expect } from "chai";
-import { createMint, createAccount, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+  createMint,
+  createAccount,
+  getAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
 import { errLogs, provider, payer, getErr } from "../helpers";
 import { Vesting } from "../vesting";
 
@@ -92,6 +98,32 @@ export function test() {
       expect(logs).to.contain("Signature verification failed");
     });
 
+    it("fails if rate limit has no maximum amount", async () => {
+      const logs = await errLogs(
+        Vesting.init({
+          vesteeWallet,
+          mint: vestingMint,
+          rateLimitWindowSecs: 3_600,
+          rateLimitMaxAmount: 0,
+        })
+      );
+
+      expect(logs).to.contain("The rate limit maximum amount must be positive");
+    });
+
+    it("is sponsored by the payer", async () => {
+      const adminKeypair = Keypair.generate();
+      await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+      });
+
+      expect(
+        await provider.connection.getBalance(adminKeypair.publicKey)
+      ).to.eq(0);
+    });
+
     it("works", async () => {
       const adminKeypair = Keypair.generate();
       const vesting = await Vesting.init({
@@ -118,6 +150,69 @@ export function test() {
       expect(vestingInfo.cumulativeWithdrawn.amount.toNumber()).to.eq(0);
       expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(0);
       expect(vestingInfo.unfundedLiability.amount.toNumber()).to.eq(0);
+      expect(vestingInfo.claimDeadline.time.toNumber()).to.eq(0);
+    });
+
+    it("fails if the associated vault is not the signer's ATA", async () => {
+      const vestingKeypair = Keypair.generate();
+      const logs = await errLogs(
+        Vesting.init({
+          keypair: vestingKeypair,
+          vesteeWallet,
+          mint: vestingMint,
+          associatedVault: true,
+          // the PDA vault instead of the associated token account
+          vestingVault: await Vesting.vaultFrom(
+            vestingKeypair.publicKey,
+            vestingMint,
+            false
+          ),
+        })
+      );
+
+      expect(logs).to.contain(
+        "Vault must be the associated token account of the vesting signer"
+      );
+    });
+
+    it("works with the associated token account as vault", async () => {
+      const vesting = await Vesting.init({
+        vesteeWallet,
+        mint: vestingMint,
+        associatedVault: true,
+        // in the past such that the tokens can be withdrawn right away
+        startTs: 1262304001,
+      });
+
+      const vestingInfo = await vesting.fetch();
+      const vault = await Vesting.vaultFrom(vesting.id, vestingMint, true);
+      expect(vestingInfo.vault).to.deep.eq(vault);
+
+      const vaultInfo = await getAccount(provider.connection, vault);
+      expect(vaultInfo.owner).to.deep.eq(await vesting.signerPda());
+      expect(vaultInfo.mint).to.deep.eq(vestingMint);
+
+      // funding and withdrawing work with either vault layout
+      const walletAuthority = Keypair.generate();
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        10_000
+      );
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 10_000);
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 10_000);
+
+      const vesteeInfo = await getAccount(provider.connection, vesteeWallet);
+      expect(Number(vesteeInfo.amount)).to.eq(10_000);
     });
   });
 }
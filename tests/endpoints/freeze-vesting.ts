@@ -0,0 +1,168 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createAccount, createMint, mintTo } from "@solana/spl-token";
+import { errLogs, payer, provider, sleep } from "../helpers";
+import { OPERATOR_WITHDRAW, Vesting } from "../vesting";
+
+export function test() {
+  describe("freeze_vesting and unfreeze_vesting", () => {
+    const walletAuthority = Keypair.generate();
+    let adminKeypair: Keypair;
+    let guardianKeypair: Keypair;
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create and fund vesting account", async () => {
+      adminKeypair = Keypair.generate();
+      guardianKeypair = Keypair.generate();
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        guardian: guardianKeypair.publicKey,
+      });
+
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+    });
+
+    const newVesteeWallet = () =>
+      createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+    it("fails if neither the admin nor the guardian signs", async () => {
+      const logs = await errLogs(vesting.freezeVesting(Keypair.generate()));
+
+      expect(logs).to.contain(
+        "Only the admin or the guardian can freeze or unfreeze the vesting account"
+      );
+    });
+
+    it("blocks withdrawals and wallet changes of the vestee", async () => {
+      await vesting.freezeVesting(guardianKeypair);
+      expect((await vesting.fetch()).frozen).to.be.true;
+
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens({ vesteeWallet }, 10)
+      );
+      expect(logs).to.contain("VestingFrozen");
+
+      const maxLogs = await errLogs(
+        vesting.withdrawMaxVestedTokens({ vesteeWallet })
+      );
+      expect(maxLogs).to.contain("VestingFrozen");
+
+      const requestLogs = await errLogs(
+        vesting.requestVesteeWalletChange(await newVesteeWallet())
+      );
+      expect(requestLogs).to.contain("VestingFrozen");
+
+      const delegateLogs = await errLogs(
+        vesting.setWithdrawalDelegate({}, Keypair.generate().publicKey)
+      );
+      expect(delegateLogs).to.contain("VestingFrozen");
+
+      const operatorLogs = await errLogs(
+        vesting.setVesteeOperator(payer, {
+          key: Keypair.generate().publicKey,
+          permissions: OPERATOR_WITHDRAW,
+          expiresAt: 0,
+        })
+      );
+      expect(operatorLogs).to.contain("VestingFrozen");
+
+      // vesting keeps accruing
+      await vesting.updateVestedTokens();
+
+      const frozenLogs = await errLogs(vesting.freezeVesting(adminKeypair));
+      expect(frozenLogs).to.contain("The vesting account is already frozen");
+
+      await vesting.unfreezeVesting(adminKeypair);
+      expect((await vesting.fetch()).frozen).to.be.false;
+
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 10);
+      expect(
+        (await vesting.fetch()).cumulativeWithdrawn.amount.toNumber()
+      ).to.eq(10);
+
+      const unfrozenLogs = await errLogs(vesting.unfreezeVesting(adminKeypair));
+      expect(unfrozenLogs).to.contain("The vesting account is not frozen");
+    });
+
+    it("lets the admin change the vestee wallet while frozen", async () => {
+      await vesting.freezeVesting(adminKeypair);
+
+      const vesteeWalletNew = await newVesteeWallet();
+      await vesting.changeVesteeWallet({ adminKeypair, vesteeWalletNew });
+
+      expect((await vesting.fetch()).vesteeWallet).to.deep.eq(vesteeWalletNew);
+    });
+
+    it("keeps the veto of the vestee on queued actions while frozen", async () => {
+      const actionDelaySecs = 2;
+      const timelocked = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        guardian: guardianKeypair.publicKey,
+        actionDelaySecs,
+      });
+      await timelocked.freezeVesting(adminKeypair);
+
+      // the vestee wallet is owned by the provider wallet
+      const vetoed = await timelocked.queueAdminAction(adminKeypair, {
+        changeVesteeWallet: { vesteeWallet: await newVesteeWallet() },
+      });
+      await timelocked.cancelAdminAction(payer, vetoed);
+      expect(await provider.connection.getAccountInfo(vetoed)).to.be.null;
+
+      // without a veto the change goes through once the delay passed
+      const vesteeWalletNew = await newVesteeWallet();
+      const queuedAction = await timelocked.queueAdminAction(adminKeypair, {
+        changeVesteeWallet: { vesteeWallet: vesteeWalletNew },
+      });
+      await sleep((actionDelaySecs + 1) * 1000);
+      await timelocked.executeAdminAction(queuedAction, [vesteeWalletNew]);
+
+      expect((await timelocked.fetch()).vesteeWallet).to.deep.eq(
+        vesteeWalletNew
+      );
+    });
+  });
+}
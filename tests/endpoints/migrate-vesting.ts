@@ -0,0 +1,94 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { errLogs, provider, vesting as program } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("migrate_vesting", () => {
+    const adminKeypair = Keypair.generate();
+    let vesting: Vesting;
+
+    beforeEach("create vesting account", async () => {
+      vesting = await Vesting.init({ adminKeypair });
+    });
+
+    it("creates vesting accounts of the current version", async () => {
+      const vestingInfo = await vesting.fetch();
+
+      expect(vestingInfo.version).to.eq(1);
+    });
+
+    it("migrates an account of the baseline layout", async () => {
+      // Loaded into the validator from tests/fixtures/legacy-vesting.json, it
+      // is 229 bytes long and holds the fields of the baseline layout only
+      const legacyId = new PublicKey(
+        "F9h7HCkLaZuuWqkcYsSdvJya91w98VcHwwymGbzrYZj4"
+      );
+      const legacyInfo = await provider.connection.getAccountInfo(legacyId);
+      expect(legacyInfo.data.length).to.eq(229);
+
+      await vesting.migrateVesting(legacyId);
+
+      const migratedInfo = await provider.connection.getAccountInfo(legacyId);
+      expect(migratedInfo.data.length).to.eq(915);
+      expect(migratedInfo.lamports).to.be.at.least(
+        await provider.connection.getMinimumBalanceForRentExemption(915)
+      );
+
+      const migrated = await program.account.vesting.fetch(legacyId);
+      expect(migrated.version).to.eq(1);
+      expect(migrated.admin).to.deep.eq(
+        new PublicKey("XgdJeEkjHpcrwmfWo3rKY4gjrXNmePs6bpCnHdSkrAk")
+      );
+      expect(migrated.vesteeWallet).to.deep.eq(
+        new PublicKey("5canB6nuHY5Ldtc8d8ctMbLJHVpJmqpUoBEKVixWJTdE")
+      );
+      expect(migrated.mint).to.deep.eq(
+        new PublicKey("JDkLGERpCAG47WifhZzreEb8KMMTrXmrTjXe78tSDfCX")
+      );
+      expect(migrated.vault).to.deep.eq(
+        new PublicKey("5GtRtDbbW7zH5C26tmuc4KuvbEQrf1WtAcFSiqsVXQuH")
+      );
+      expect(migrated.totalVesting.amount.toNumber()).to.eq(10_000);
+      expect(migrated.cumulativeVested.amount.toNumber()).to.eq(2_500);
+      expect(migrated.cumulativeWithdrawn.amount.toNumber()).to.eq(1_000);
+      expect(migrated.vaultBalance.amount.toNumber()).to.eq(1_500);
+      // Jan 01 2020
+      expect(migrated.startTs.time.toNumber()).to.eq(1577836800);
+      expect(migrated.totalPeriods.toNumber()).to.eq(48);
+      expect(migrated.cliffPeriods.toNumber()).to.eq(12);
+      expect(migrated.periodType).to.deep.eq({ daily: {} });
+
+      // The fields added since start at their defaults
+      expect(migrated.claimDeadline.time.toNumber()).to.eq(0);
+      expect(migrated.rateLimit.windowSecs.toNumber()).to.eq(0);
+      expect(migrated.withdrawalDelegate).to.deep.eq(PublicKey.default);
+      expect(migrated.beneficiaryOnly).to.be.false;
+      expect(migrated.frozen).to.be.false;
+      expect(migrated.registered).to.be.false;
+      expect(migrated.vesteeWalletOwner).to.deep.eq(PublicKey.default);
+      expect(migrated.claimableBalance.amount.toNumber()).to.eq(0);
+
+      const logs = await errLogs(vesting.migrateVesting(legacyId));
+      expect(logs).to.contain(
+        "Vesting account is already of the current version"
+      );
+    });
+
+    it("fails if already of the current version", async () => {
+      const logs = await errLogs(vesting.migrateVesting());
+
+      expect(logs).to.contain(
+        "Vesting account is already of the current version"
+      );
+    });
+
+    it("fails if not a vesting account", async () => {
+      const registry = Vesting.registryFrom("admin", adminKeypair.publicKey, 0);
+
+      const logs = await errLogs(vesting.migrateVesting(registry));
+
+      expect(logs).to.contain("AccountDiscriminatorMismatch");
+    });
+  });
+}
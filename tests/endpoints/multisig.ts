@@ -0,0 +1,121 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { BN } from "@project-serum/anchor";
+import { expect } from "chai";
+import { errLogs, provider, vesting as program } from "../helpers";
+import { Multisig } from "../multisig";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("multisig admin", () => {
+    let members: Keypair[];
+    let multisig: Multisig;
+    let vesting: Vesting;
+
+    beforeEach("create multisig admin of a vesting account", async () => {
+      members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+      multisig = await Multisig.init(members, 2);
+
+      const adminKeypair = Keypair.generate();
+      vesting = await Vesting.init({ adminKeypair });
+      await vesting.proposeAdmin(adminKeypair, multisig.signer);
+
+      const acceptIx = await program.methods
+        .acceptAdmin()
+        .accounts({ pendingAdmin: multisig.signer, vesting: vesting.id })
+        .instruction();
+      await multisig.invoke(acceptIx, [members[0], members[1]]);
+
+      expect((await vesting.fetch()).admin).to.deep.eq(multisig.signer);
+    });
+
+    const setBlackoutIx = () =>
+      program.methods
+        .setBlackoutWindows([
+          { start: { time: new BN(0) }, end: { time: new BN(1) } },
+        ])
+        .accounts({ admin: multisig.signer, vesting: vesting.id })
+        .instruction();
+
+    it("fails to create with a threshold above the member count", async () => {
+      const logs = await errLogs(Multisig.init(members, 4));
+
+      expect(logs).to.contain(
+        "The threshold must be positive and at most the number of members"
+      );
+    });
+
+    it("fails to invoke without enough member signatures", async () => {
+      const logs = await errLogs(
+        multisig.invoke(await setBlackoutIx(), [members[0], Keypair.generate()])
+      );
+
+      expect(logs).to.contain(
+        "Not enough members of the multisig signed the transaction"
+      );
+    });
+
+    it("invokes an admin endpoint with enough signatures at once", async () => {
+      await multisig.invoke(await setBlackoutIx(), [members[1], members[2]]);
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.blackoutWindows[0].end.time.toNumber()).to.eq(1);
+    });
+
+    it("executes a proposal once enough members approved", async () => {
+      const ix = await setBlackoutIx();
+      const proposal = await multisig.propose(ix, members[0]);
+
+      let logs = await errLogs(multisig.execute(proposal, ix));
+      expect(logs).to.contain(
+        "The proposal has not been approved by enough members of the multisig"
+      );
+
+      logs = await errLogs(multisig.approve(proposal, Keypair.generate()));
+      expect(logs).to.contain("Signer is not a member of the multisig");
+
+      await multisig.approve(proposal, members[2]);
+      await multisig.execute(proposal, ix);
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.blackoutWindows[0].end.time.toNumber()).to.eq(1);
+
+      // the proposal is closed
+      expect(await provider.connection.getAccountInfo(proposal)).to.be.null;
+    });
+
+    it("invalidates proposals when the members change", async () => {
+      const ix = await setBlackoutIx();
+      const proposal = await multisig.propose(ix, members[0]);
+      await multisig.approve(proposal, members[1]);
+
+      const newMembers = [members[0], Keypair.generate()];
+      const changeIx = await program.methods
+        .changeMultisig(
+          newMembers.map((m) => m.publicKey),
+          2
+        )
+        .accounts({ multisig: multisig.id, multisigSigner: multisig.signer })
+        .instruction();
+      await multisig.invoke(changeIx, [members[0], members[2]]);
+
+      const multisigInfo = await multisig.fetch();
+      expect(multisigInfo.memberCount).to.eq(2);
+
+      const logs = await errLogs(multisig.execute(proposal, ix));
+      expect(logs).to.contain(
+        "The members of the multisig changed since the proposal was made"
+      );
+
+      await multisig.cancel(proposal);
+      expect(await provider.connection.getAccountInfo(proposal)).to.be.null;
+    });
+
+    it("fails if a single key signs as the admin", async () => {
+      const logs = await errLogs(
+        vesting.setBlackoutWindows({ adminKeypair: members[0] }, [])
+      );
+
+      expect(logs).to.contain("Vesting admin does not match the provided signer");
+    });
+  });
+}
@@ -0,0 +1,150 @@
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createAccount,
+  createMint,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { daoStub, errLogs, payer, provider, vesting as program } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("program admin over CPI", () => {
+    const [governance, _bumpSeed] = PublicKey.findProgramAddressSync(
+      [Buffer.from("governance")],
+      daoStub.programId
+    );
+    let mint: PublicKey;
+    let vesting: Vesting;
+
+    const newWallet = () =>
+      createAccount(
+        provider.connection,
+        payer,
+        mint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+    beforeEach("create vesting account via the dao", async () => {
+      mint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        6
+      );
+      const vestingKeypair = Keypair.generate();
+      const vesteeWallet = await newWallet();
+      const vestingVault = await Vesting.vaultFrom(
+        vestingKeypair.publicKey,
+        mint,
+        false
+      );
+
+      await daoStub.methods
+        .createVestingSchedule(
+          ...Vesting.scheduleArgs({
+            // the schedule ended in 2014 and could be claimed until 2015,
+            // hence it can be closed right away
+            startTs: 1262304001,
+            claimDeadline: 1420070400,
+          })
+        )
+        .accounts({
+          governance,
+          payer: payer.publicKey,
+          vesting: vestingKeypair.publicKey,
+          vestingSigner: await Vesting.signerFrom(vestingKeypair.publicKey).then(
+            ([pda, _]) => pda
+          ),
+          vestingVault,
+          mint,
+          vesteeWallet,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+          vestingTreasury: program.programId,
+        })
+        .remainingAccounts(
+          await Vesting.registryAccountsWithRoom(
+            false,
+            governance,
+            payer.publicKey
+          )
+        )
+        .signers([vestingKeypair])
+        .rpc();
+
+      vesting = new Vesting(vestingKeypair, payer, mint);
+      vesting.vault = vestingVault;
+      vesting.vesteeOwner = payer.publicKey;
+    });
+
+    it("is administered by the program derived address", async () => {
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.admin).to.deep.eq(governance);
+
+      // the governance PDA holds no lamports
+      expect(await provider.connection.getAccountInfo(governance)).to.be.null;
+    });
+
+    it("changes the vestee wallet via the dao", async () => {
+      const vesteeWalletNew = await newWallet();
+
+      await daoStub.methods
+        .changeVesteeWallet()
+        .accounts({
+          governance,
+          vesting: vesting.id,
+          vesteeWalletNew,
+          vestingTreasury: program.programId,
+        })
+        .rpc();
+
+      expect((await vesting.fetch()).vesteeWallet).to.deep.eq(vesteeWalletNew);
+    });
+
+    it("fails if the dao is bypassed", async () => {
+      const logs = await errLogs(
+        vesting.changeVesteeWallet({ vesteeWalletNew: await newWallet() })
+      );
+
+      expect(logs).to.contain(
+        "Vesting wallet manager does not match the provided signer"
+      );
+    });
+
+    it("closes via the dao and refunds the rent to the receiver", async () => {
+      const rentReceiver = Keypair.generate().publicKey;
+      const vestingLamports = (
+        await provider.connection.getAccountInfo(vesting.id)
+      ).lamports;
+      const vaultLamports = (
+        await provider.connection.getAccountInfo(await vesting.vestingVault())
+      ).lamports;
+
+      await daoStub.methods
+        .closeVestingSchedule()
+        .accounts({
+          governance,
+          vesting: vesting.id,
+          vestingVault: await vesting.vestingVault(),
+          vestingSigner: await vesting.signerPda(),
+          recoveryWallet: await newWallet(),
+          rentReceiver,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          vestingTreasury: program.programId,
+        })
+        .remainingAccounts(await vesting.registryAccounts())
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(vesting.id)).to.be.null;
+      expect(await provider.connection.getBalance(rentReceiver)).to.eq(
+        vestingLamports + vaultLamports
+      );
+    });
+  });
+}
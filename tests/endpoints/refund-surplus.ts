@@ -0,0 +1,101 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createMint,
+  createAccount,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("refund_surplus", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    let vestingMint: PublicKey;
+    let fundingWallet: PublicKey;
+    let refundWallet: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create vesting account", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+
+      vesting = await Vesting.init({
+        adminKeypair,
+        mint: vestingMint,
+        vestingAmount: 10_000,
+      });
+    });
+
+    beforeEach("create funding and refund wallets", async () => {
+      fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+
+      refundWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+    });
+
+    it("fails if wrong admin", async () => {
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 12_000);
+
+      const logs = await errLogs(
+        vesting.refundSurplus(
+          { adminKeypair: Keypair.generate(), refundWallet },
+          1_000
+        )
+      );
+
+      expect(logs).to.contain("Vesting funder does not match the provided signer");
+    });
+
+    it("fails if the amount exceeds the surplus", async () => {
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 12_000);
+
+      const logs = await errLogs(
+        vesting.refundSurplus({ refundWallet }, 2_001)
+      );
+
+      expect(logs).to.contain(
+        "The refund amount exceeds the surplus over the remaining obligation"
+      );
+    });
+
+    it("works", async () => {
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 12_000);
+
+      await vesting.refundSurplus({ refundWallet }, 2_000);
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(10_000);
+
+      const refundInfo = await getAccount(provider.connection, refundWallet);
+      expect(Number(refundInfo.amount)).to.eq(2_000);
+
+      const vaultInfo = await vesting.vestingVaultInfo();
+      expect(Number(vaultInfo.amount)).to.eq(10_000);
+    });
+  });
+}
@@ -0,0 +1,132 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { closeAccount, createAccount, createMint } from "@solana/spl-token";
+import { errLogs, payer, provider } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("request_vestee_wallet_change and approve_vestee_wallet_change", () => {
+    let adminKeypair: Keypair;
+    let vesting: Vesting;
+
+    const newVesteeWallet = (mint?: PublicKey) =>
+      createAccount(
+        provider.connection,
+        payer,
+        mint ?? vesting.mint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+    beforeEach("create vesting account", async () => {
+      adminKeypair = Keypair.generate();
+      vesting = await Vesting.init({ adminKeypair });
+    });
+
+    it("fails if not the owner of the vestee wallet", async () => {
+      const logs = await errLogs(
+        vesting.requestVesteeWalletChange(
+          await newVesteeWallet(),
+          Keypair.generate()
+        )
+      );
+
+      expect(logs).to.contain(
+        "Only the owner of the vestee wallet can request a change"
+      );
+    });
+
+    it("lets the vestee move away from a closed wallet", async () => {
+      const { vesteeWallet } = await vesting.fetch();
+      await closeAccount(
+        provider.connection,
+        payer,
+        vesteeWallet,
+        payer.publicKey,
+        payer
+      );
+      const requested = await newVesteeWallet();
+
+      await vesting.requestVesteeWalletChange(requested);
+
+      expect((await vesting.fetch()).pendingVesteeWallet).to.deep.eq(
+        requested
+      );
+    });
+
+    it("fails if the new wallet is of another mint", async () => {
+      const otherMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        6
+      );
+
+      const logs = await errLogs(
+        vesting.requestVesteeWalletChange(await newVesteeWallet(otherMint))
+      );
+
+      expect(logs).to.contain(
+        "The new vestee wallet mint must be of correct mint"
+      );
+    });
+
+    it("waits for the approval of the wallet manager", async () => {
+      const { vesteeWallet } = await vesting.fetch();
+      const requested = await newVesteeWallet();
+
+      await vesting.requestVesteeWalletChange(requested);
+
+      let vestingInfo = await vesting.fetch();
+      expect(vestingInfo.vesteeWallet).to.deep.eq(vesteeWallet);
+      expect(vestingInfo.pendingVesteeWallet).to.deep.eq(requested);
+
+      const logs = await errLogs(
+        vesting.approveVesteeWalletChange(adminKeypair, await newVesteeWallet())
+      );
+      expect(logs).to.contain(
+        "The vestee wallet does not match the one requested by the vestee"
+      );
+
+      await vesting.approveVesteeWalletChange(adminKeypair, requested);
+
+      vestingInfo = await vesting.fetch();
+      expect(vestingInfo.vesteeWallet).to.deep.eq(requested);
+      expect(vestingInfo.pendingVesteeWallet).to.deep.eq(PublicKey.default);
+    });
+
+    it("fails to approve if not the wallet manager", async () => {
+      const requested = await newVesteeWallet();
+      await vesting.requestVesteeWalletChange(requested);
+
+      const logs = await errLogs(
+        vesting.approveVesteeWalletChange(Keypair.generate(), requested)
+      );
+
+      expect(logs).to.contain(
+        "Vesting wallet manager does not match the provided signer"
+      );
+    });
+
+    it("changes immediately if the policy allows it", async () => {
+      const v = await Vesting.init({
+        adminKeypair,
+        immediateVesteeWalletChange: true,
+      });
+      const requested = await createAccount(
+        provider.connection,
+        payer,
+        v.mint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+      await v.requestVesteeWalletChange(requested);
+
+      const vestingInfo = await v.fetch();
+      expect(vestingInfo.vesteeWallet).to.deep.eq(requested);
+      expect(vestingInfo.pendingVesteeWallet).to.deep.eq(PublicKey.default);
+    });
+  });
+}
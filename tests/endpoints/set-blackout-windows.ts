@@ -0,0 +1,160 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createMint, createAccount } from "@solana/spl-token";
+import { errLogs, provider, payer, getErr } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("set_blackout_windows", () => {
+    const adminKeypair = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create vesting mint", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee wallet", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey
+      );
+    });
+
+    beforeEach("create vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+      });
+    });
+
+    it("fails if wrong admin", async () => {
+      const fakeAdminKeypair = Keypair.generate();
+
+      const logs = await errLogs(
+        vesting.setBlackoutWindows({ adminKeypair: fakeAdminKeypair }, [])
+      );
+
+      expect(logs).to.contain(
+        "Vesting admin does not match the provided signer"
+      );
+    });
+
+    it("fails if admin isn't signer", async () => {
+      const logs = await getErr(
+        vesting.setBlackoutWindows(
+          { adminKeypair, skipAdminSignature: true },
+          []
+        )
+      );
+
+      expect(logs).to.contain("Signature verification failed");
+    });
+
+    it("fails if window ends before it starts", async () => {
+      const logs = await errLogs(
+        vesting.setBlackoutWindows({ adminKeypair }, [
+          { start: 1_700_000_000, end: 1_600_000_000 },
+        ])
+      );
+
+      expect(logs).to.contain("Blackout window start must be before its end");
+    });
+
+    it("fails if too many windows", async () => {
+      const windows = Array.from({ length: 9 }, (_, i) => ({
+        start: 1_600_000_000 + i * 100,
+        end: 1_600_000_000 + i * 100 + 50,
+      }));
+
+      const logs = await errLogs(
+        vesting.setBlackoutWindows({ adminKeypair }, windows)
+      );
+
+      expect(logs).to.contain("At most 8 blackout windows are supported");
+    });
+
+    it("fails if window is longer than thirty days", async () => {
+      const logs = await errLogs(
+        vesting.setBlackoutWindows({ adminKeypair }, [
+          { start: 1_600_000_000, end: 1_600_000_000 + 30 * 86_400 + 1 },
+        ])
+      );
+
+      expect(logs).to.contain(
+        "A blackout window can be at most 2592000 seconds long"
+      );
+    });
+
+    it("fails if windows overlap", async () => {
+      const logs = await errLogs(
+        vesting.setBlackoutWindows({ adminKeypair }, [
+          { start: 1_600_000_000, end: 1_600_100_000 },
+          { start: 1_600_050_000, end: 1_600_200_000 },
+        ])
+      );
+
+      expect(logs).to.contain(
+        "Blackout windows must be sorted by their start and must not overlap"
+      );
+    });
+
+    it("fails if window reaches the claim deadline", async () => {
+      const claimDeadline = 4_000_000_000;
+      const deadlineVesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        claimDeadline,
+      });
+
+      const logs = await errLogs(
+        deadlineVesting.setBlackoutWindows({ adminKeypair }, [
+          { start: claimDeadline - 3600, end: claimDeadline },
+        ])
+      );
+
+      expect(logs).to.contain(
+        "Blackout windows must end before the claim deadline"
+      );
+    });
+
+    it("works", async () => {
+      await vesting.setBlackoutWindows({ adminKeypair }, [
+        { start: 1_600_000_000, end: 1_600_100_000 },
+        { start: 1_700_000_000, end: 1_700_100_000 },
+      ]);
+
+      const vestingInfo = await vesting.fetch();
+      const windows = vestingInfo.blackoutWindows.map((window) => ({
+        start: window.start.time.toNumber(),
+        end: window.end.time.toNumber(),
+      }));
+
+      expect(windows).to.deep.eq([
+        { start: 1_600_000_000, end: 1_600_100_000 },
+        { start: 1_700_000_000, end: 1_700_100_000 },
+        ...Array(6).fill({ start: 0, end: 0 }),
+      ]);
+
+      await vesting.setBlackoutWindows({ adminKeypair }, []);
+
+      const vestingInfoAfter = await vesting.fetch();
+      expect(
+        vestingInfoAfter.blackoutWindows.every(
+          (window) => window.end.time.toNumber() === 0
+        )
+      ).to.be.true;
+    });
+  });
+}
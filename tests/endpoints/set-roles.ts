@@ -0,0 +1,87 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createAccount } from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("set_roles", () => {
+    let adminKeypair: Keypair;
+    let walletManagerKeypair: Keypair;
+    let vesting: Vesting;
+
+    beforeEach("create vesting account", async () => {
+      adminKeypair = Keypair.generate();
+      walletManagerKeypair = Keypair.generate();
+      vesting = await Vesting.init({ adminKeypair });
+    });
+
+    const newVesteeWallet = () =>
+      createAccount(
+        provider.connection,
+        payer,
+        vesting.mint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+
+    it("fails if wrong admin", async () => {
+      const logs = await errLogs(
+        vesting.setRoles(Keypair.generate(), {
+          walletManager: walletManagerKeypair.publicKey,
+        })
+      );
+
+      expect(logs).to.contain("Vesting admin does not match the provided signer");
+    });
+
+    it("sets roles at creation", async () => {
+      const closer = Keypair.generate().publicKey;
+      const v = await Vesting.init({ adminKeypair, roles: { closer } });
+
+      const vestingInfo = await v.fetch();
+      expect(vestingInfo.roles.closer).to.deep.eq(closer);
+      expect(vestingInfo.roles.funder).to.deep.eq(PublicKey.default);
+    });
+
+    it("delegates a power of the admin to a role", async () => {
+      await vesting.setRoles(adminKeypair, {
+        walletManager: walletManagerKeypair.publicKey,
+      });
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.roles.walletManager).to.deep.eq(
+        walletManagerKeypair.publicKey
+      );
+
+      // the admin no longer holds the power
+      const logs = await errLogs(
+        vesting.changeVesteeWallet({
+          adminKeypair,
+          vesteeWalletNew: await newVesteeWallet(),
+        })
+      );
+      expect(logs).to.contain(
+        "Vesting wallet manager does not match the provided signer"
+      );
+
+      const vesteeWalletNew = await newVesteeWallet();
+      await vesting.changeVesteeWallet({
+        adminKeypair: walletManagerKeypair,
+        vesteeWalletNew,
+      });
+      expect((await vesting.fetch()).vesteeWallet).to.deep.eq(vesteeWalletNew);
+    });
+
+    it("falls back to the admin when a role is unset", async () => {
+      await vesting.setRoles(adminKeypair, {
+        walletManager: walletManagerKeypair.publicKey,
+      });
+      await vesting.setRoles(adminKeypair, {});
+
+      const vesteeWalletNew = await newVesteeWallet();
+      await vesting.changeVesteeWallet({ adminKeypair, vesteeWalletNew });
+      expect((await vesting.fetch()).vesteeWallet).to.deep.eq(vesteeWalletNew);
+    });
+  });
+}
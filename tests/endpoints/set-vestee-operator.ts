@@ -0,0 +1,239 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AuthorityType,
+  createMint,
+  createAccount,
+  mintTo,
+  setAuthority,
+} from "@solana/spl-token";
+import { errLogs, provider, payer, airdrop, sleep } from "../helpers";
+import {
+  OPERATOR_CANCEL_ADMIN_ACTION,
+  OPERATOR_WITHDRAW,
+  Vesting,
+} from "../vesting";
+
+export function test() {
+  describe("set_vestee_operator", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    const vesteeKeypair = Keypair.generate();
+    const operatorKeypair = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    before("airdrop SOL to vestee and operator", async () => {
+      await airdrop(vesteeKeypair.publicKey);
+      await airdrop(operatorKeypair.publicKey);
+    });
+
+    beforeEach("create vesting mint and vestee wallet", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        vesteeKeypair.publicKey
+      );
+    });
+
+    beforeEach("create and fund vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        beneficiaryOnly: true,
+        actionDelaySecs: 60,
+      });
+
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+    });
+
+    const withdrawAsOperator = () =>
+      vesting.withdrawVestedTokens(
+        { vesteeWallet, withdrawAuthority: operatorKeypair },
+        10
+      );
+
+    it("fails if signer does not own vestee wallet", async () => {
+      const logs = await errLogs(
+        vesting.setVesteeOperator(operatorKeypair, {
+          key: operatorKeypair.publicKey,
+          permissions: OPERATOR_WITHDRAW,
+          expiresAt: 0,
+        })
+      );
+
+      expect(logs).to.contain("Signer does not own the vestee wallet");
+    });
+
+    it("follows the recorded owner of the vestee wallet", async () => {
+      await setAuthority(
+        provider.connection,
+        payer,
+        vesteeWallet,
+        vesteeKeypair,
+        AuthorityType.AccountOwner,
+        operatorKeypair.publicKey
+      );
+
+      const logs = await errLogs(
+        vesting.setVesteeOperator(operatorKeypair, {
+          key: operatorKeypair.publicKey,
+          permissions: OPERATOR_WITHDRAW,
+          expiresAt: 0,
+        })
+      );
+      expect(logs).to.contain("Signer does not own the vestee wallet");
+
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: OPERATOR_CANCEL_ADMIN_ACTION,
+        expiresAt: 0,
+      });
+      expect((await vesting.fetch()).operators[0].key).to.deep.eq(
+        operatorKeypair.publicKey
+      );
+    });
+
+    it("lets the operator withdraw to the vestee wallet", async () => {
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: OPERATOR_WITHDRAW,
+        expiresAt: 0,
+      });
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.operators[0].key).to.deep.eq(
+        operatorKeypair.publicKey
+      );
+      expect(vestingInfo.operators[0].permissions).to.eq(OPERATOR_WITHDRAW);
+
+      await withdrawAsOperator();
+      expect(
+        (await vesting.fetch()).cumulativeWithdrawn.amount.toNumber()
+      ).to.eq(10);
+
+      // the operator cannot cancel admin actions without the permission
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        proposeAdmin: { pendingAdmin: Keypair.generate().publicKey },
+      });
+      const logs = await errLogs(
+        vesting.cancelAdminAction(operatorKeypair, queuedAction)
+      );
+      expect(logs).to.contain(
+        "Only the vestee, their operator, the guardian or the admin can cancel a queued action"
+      );
+    });
+
+    it("does not let the operator change the vestee wallet", async () => {
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: OPERATOR_WITHDRAW | OPERATOR_CANCEL_ADMIN_ACTION,
+        expiresAt: 0,
+      });
+
+      const vesteeWalletNew = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        operatorKeypair.publicKey
+      );
+      const logs = await errLogs(
+        vesting.requestVesteeWalletChange(vesteeWalletNew, operatorKeypair)
+      );
+      expect(logs).to.contain(
+        "Only the owner of the vestee wallet can request a change"
+      );
+
+      const operatorLogs = await errLogs(
+        vesting.setVesteeOperator(operatorKeypair, {
+          key: Keypair.generate().publicKey,
+          permissions: OPERATOR_WITHDRAW,
+          expiresAt: 0,
+        })
+      );
+      expect(operatorLogs).to.contain("Signer does not own the vestee wallet");
+    });
+
+    it("lets the operator cancel admin actions", async () => {
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: OPERATOR_CANCEL_ADMIN_ACTION,
+        expiresAt: 0,
+      });
+
+      const queuedAction = await vesting.queueAdminAction(adminKeypair, {
+        proposeAdmin: { pendingAdmin: Keypair.generate().publicKey },
+      });
+      await vesting.cancelAdminAction(operatorKeypair, queuedAction);
+
+      expect(await provider.connection.getAccountInfo(queuedAction)).to.be
+        .null;
+
+      const logs = await errLogs(withdrawAsOperator());
+      expect(logs).to.contain(
+        "Withdrawals from this vesting account must be signed by the vestee, their delegate or an operator"
+      );
+    });
+
+    it("takes the rights away on expiry and on removal", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: OPERATOR_WITHDRAW,
+        expiresAt: now + 3,
+      });
+      await withdrawAsOperator();
+
+      await sleep(5_000);
+      const logs = await errLogs(withdrawAsOperator());
+      expect(logs).to.contain(
+        "Withdrawals from this vesting account must be signed by the vestee, their delegate or an operator"
+      );
+
+      // renewing the operator reuses its slot
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: OPERATOR_WITHDRAW,
+        expiresAt: 0,
+      });
+      await withdrawAsOperator();
+
+      await vesting.setVesteeOperator(vesteeKeypair, {
+        key: operatorKeypair.publicKey,
+        permissions: 0,
+        expiresAt: 0,
+      });
+      expect((await vesting.fetch()).operators[0].key).to.deep.eq(
+        PublicKey.default
+      );
+      await errLogs(withdrawAsOperator());
+    });
+  });
+}
@@ -0,0 +1,207 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  AuthorityType,
+  createMint,
+  createAccount,
+  getAccount,
+  mintTo,
+  setAuthority,
+} from "@solana/spl-token";
+import { errLogs, provider, payer, airdrop } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("set_withdrawal_delegate", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    const vesteeKeypair = Keypair.generate();
+    const delegateKeypair = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let fundingWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    before("airdrop SOL to vestee and delegate", async () => {
+      await airdrop(vesteeKeypair.publicKey);
+      await airdrop(delegateKeypair.publicKey);
+    });
+
+    beforeEach("create vesting mint", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee wallet", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        vesteeKeypair.publicKey
+      );
+    });
+
+    beforeEach("create vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        beneficiaryOnly: true,
+      });
+    });
+
+    beforeEach("fund vesting vault", async () => {
+      fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+    });
+
+    it("fails if signer does not own vestee wallet", async () => {
+      const logs = await errLogs(
+        vesting.setWithdrawalDelegate(
+          { vesteeKeypair: delegateKeypair },
+          delegateKeypair.publicKey
+        )
+      );
+
+      expect(logs).to.contain("Signer does not own the vestee wallet");
+    });
+
+    it("follows the recorded owner of the vestee wallet", async () => {
+      await setAuthority(
+        provider.connection,
+        payer,
+        vesteeWallet,
+        vesteeKeypair,
+        AuthorityType.AccountOwner,
+        delegateKeypair.publicKey
+      );
+
+      const logs = await errLogs(
+        vesting.setWithdrawalDelegate(
+          { vesteeKeypair: delegateKeypair },
+          delegateKeypair.publicKey
+        )
+      );
+      expect(logs).to.contain("Signer does not own the vestee wallet");
+
+      await vesting.setWithdrawalDelegate(
+        { vesteeKeypair },
+        delegateKeypair.publicKey
+      );
+      expect((await vesting.fetch()).withdrawalDelegate).to.deep.eq(
+        delegateKeypair.publicKey
+      );
+    });
+
+    it("escrows to a reassigned vestee wallet", async () => {
+      const newOwner = Keypair.generate();
+      await setAuthority(
+        provider.connection,
+        payer,
+        vesteeWallet,
+        vesteeKeypair,
+        AuthorityType.AccountOwner,
+        newOwner.publicKey
+      );
+
+      // the new owner of the token account is not the vestee
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens(
+          { vesteeWallet, withdrawAuthority: newOwner },
+          10
+        )
+      );
+      expect(logs).to.contain(
+        "Withdrawals from this vesting account must be signed by the vestee, their delegate or an operator"
+      );
+
+      await vesting.withdrawVestedTokens(
+        { vesteeWallet, withdrawAuthority: vesteeKeypair },
+        10
+      );
+
+      expect(
+        (await vesting.fetch()).claimableBalance.amount.toNumber()
+      ).to.eq(10);
+      const wallet = await getAccount(provider.connection, vesteeWallet);
+      expect(Number(wallet.amount)).to.eq(0);
+    });
+
+    it("fails to withdraw if third party signs", async () => {
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens({ vesteeWallet }, 10)
+      );
+
+      expect(logs).to.contain(
+        "Withdrawals from this vesting account must be signed by the vestee, their delegate or an operator"
+      );
+    });
+
+    it("works", async () => {
+      await vesting.withdrawVestedTokens(
+        { vesteeWallet, withdrawAuthority: vesteeKeypair },
+        10
+      );
+
+      await errLogs(
+        vesting.withdrawVestedTokens(
+          { vesteeWallet, withdrawAuthority: delegateKeypair },
+          10
+        )
+      );
+
+      await vesting.setWithdrawalDelegate(
+        { vesteeKeypair },
+        delegateKeypair.publicKey
+      );
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.beneficiaryOnly).to.be.true;
+      expect(vestingInfo.withdrawalDelegate).to.deep.eq(
+        delegateKeypair.publicKey
+      );
+
+      await vesting.withdrawVestedTokens(
+        { vesteeWallet, withdrawAuthority: delegateKeypair },
+        10
+      );
+
+      const vestingInfoAfter = await vesting.fetch();
+      expect(vestingInfoAfter.cumulativeWithdrawn.amount.toNumber()).to.eq(20);
+
+      // Removing the delegate takes its rights away
+      await vesting.setWithdrawalDelegate({ vesteeKeypair }, PublicKey.default);
+
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens(
+          { vesteeWallet, withdrawAuthority: delegateKeypair },
+          10
+        )
+      );
+      expect(logs).to.contain(
+        "Withdrawals from this vesting account must be signed by the vestee, their delegate or an operator"
+      );
+    });
+  });
+}
@@ -0,0 +1,193 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { errLogs, provider, payer, getErr } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("sweep_unclaimed_tokens", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let recoveryWallet: PublicKey;
+    let fundingWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create vesting mint", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee and recovery wallets", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey
+      );
+      recoveryWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+    });
+
+    beforeEach("create vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        // Jan 01 2010, such that the schedule is fully vested by Jan 01 2014
+        startTs: 1262304001,
+        // Jan 01 2015
+        claimDeadline: 1420070401,
+      });
+    });
+
+    beforeEach("create funding wallet", async () => {
+      fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+    });
+
+    it("fails to create if deadline is before end of schedule", async () => {
+      const logs = await errLogs(
+        Vesting.init({
+          adminKeypair,
+          vesteeWallet,
+          mint: vestingMint,
+          startTs: 1262304001,
+          // Jan 01 2013
+          claimDeadline: 1356998401,
+        })
+      );
+
+      expect(logs).to.contain(
+        "The claim deadline cannot be before the end of the vesting schedule"
+      );
+    });
+
+    it("fails if wrong admin", async () => {
+      const logs = await errLogs(
+        vesting.sweepUnclaimedTokens({
+          adminKeypair: Keypair.generate(),
+          recoveryWallet,
+        })
+      );
+
+      expect(logs).to.contain(
+        "Vesting revoker does not match the provided signer"
+      );
+    });
+
+    it("fails if claim deadline has not passed", async () => {
+      const vesting2 = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        startTs: 1262304001,
+        // Jan 01 2100
+        claimDeadline: 4102444801,
+      });
+
+      const logs = await errLogs(
+        vesting2.sweepUnclaimedTokens({ adminKeypair, recoveryWallet })
+      );
+
+      expect(logs).to.contain(
+        "The claim deadline of this vesting account has not passed yet"
+      );
+    });
+
+    it("fails to withdraw after claim deadline", async () => {
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 10);
+
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens({ vesteeWallet }, 10)
+      );
+
+      expect(logs).to.contain(
+        "The claim deadline of this vesting schedule has passed"
+      );
+    });
+
+    it("fails while the vesting account is frozen", async () => {
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 10);
+      await vesting.freezeVesting(adminKeypair);
+
+      const logs = await errLogs(
+        vesting.sweepUnclaimedTokens({ adminKeypair, recoveryWallet })
+      );
+      expect(logs).to.contain("VestingFrozen");
+
+      await vesting.unfreezeVesting(adminKeypair);
+      await vesting.sweepUnclaimedTokens({ adminKeypair, recoveryWallet });
+      expect((await vesting.fetch()).vaultBalance.amount.toNumber()).to.eq(0);
+    });
+
+    it("fails to close before sweeping", async () => {
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 10);
+
+      const logs = await errLogs(
+        vesting.closeVestingSchedule({ adminKeypair })
+      );
+
+      expect(logs).to.contain(
+        "The unclaimed tokens must be swept before closing the vesting account"
+      );
+    });
+
+    it("works", async () => {
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault(
+        { walletAuthority, fundingWallet },
+        10_000
+      );
+
+      await vesting.sweepUnclaimedTokens({ adminKeypair, recoveryWallet });
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(0);
+      expect(vestingInfo.cumulativeWithdrawn.amount.toNumber()).to.eq(0);
+
+      const vaultInfo = await vesting.vestingVaultInfo();
+      expect(Number(vaultInfo.amount)).to.eq(0);
+
+      const recoveryInfo = await getAccount(provider.connection, recoveryWallet);
+      expect(Number(recoveryInfo.amount)).to.eq(10_000);
+
+      await vesting.closeVestingSchedule({ adminKeypair });
+
+      const logs = await getErr(vesting.fetch());
+      expect(logs).to.contain("Account does not exist");
+    });
+  });
+}
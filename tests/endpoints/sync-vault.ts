@@ -0,0 +1,101 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createMint, createAccount, mintTo } from "@solana/spl-token";
+import { errLogs, provider, payer, sleep, vesting as program } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("sync_vault", () => {
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create vesting account", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+
+      vesting = await Vesting.init({
+        mint: vestingMint,
+        // in the past such that the schedule is fully vested
+        startTs: 1262304001,
+      });
+    });
+
+    it("fails if wrong vault", async () => {
+      const fakeVault = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        Keypair.generate().publicKey
+      );
+
+      const logs = await errLogs(vesting.syncVault(fakeVault));
+
+      expect(logs).to.contain(
+        "Vault input does not match the vault in the vesting account"
+      );
+    });
+
+    it("records tokens sent straight to the vault as funding", async () => {
+      await vesting.updateVestedTokens();
+      let vestingInfo = await vesting.fetch();
+      expect(vestingInfo.unfundedLiability.amount.toNumber()).to.eq(10_000);
+
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        await vesting.vestingVault(),
+        payer.publicKey,
+        4_000
+      );
+      await vesting.syncVault();
+
+      vestingInfo = await vesting.fetch();
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(4_000);
+      expect(vestingInfo.unfundedLiability.amount.toNumber()).to.eq(6_000);
+
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        await vesting.vestingVault(),
+        payer.publicKey,
+        7_000
+      );
+      await vesting.syncVault();
+
+      vestingInfo = await vesting.fetch();
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(11_000);
+      expect(vestingInfo.unfundedLiability.amount.toNumber()).to.eq(0);
+    });
+
+    it("emits the previous and the new vault balance", async () => {
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        await vesting.vestingVault(),
+        payer.publicKey,
+        2_500
+      );
+
+      const events = [];
+      const listener = program.addEventListener("VaultSynced", (event) =>
+        events.push(event)
+      );
+      await vesting.syncVault();
+      await sleep(1000);
+      await program.removeEventListener(listener);
+
+      expect(events).to.have.lengthOf(1);
+      expect(events[0].vesting).to.deep.eq(vesting.id);
+      expect(events[0].previousVaultBalance.amount.toNumber()).to.eq(0);
+      expect(events[0].vaultBalance.amount.toNumber()).to.eq(2_500);
+    });
+  });
+}
@@ -0,0 +1,158 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { errLogs, vesting as program } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("propose_admin, accept_admin and rotate_admin_batch", () => {
+    let adminKeypair: Keypair;
+    let nomineeKeypair: Keypair;
+    let vesting: Vesting;
+
+    beforeEach("create vesting account", async () => {
+      adminKeypair = Keypair.generate();
+      nomineeKeypair = Keypair.generate();
+      vesting = await Vesting.init({ adminKeypair });
+    });
+
+    it("fails to propose if wrong admin", async () => {
+      const logs = await errLogs(
+        vesting.proposeAdmin(Keypair.generate(), nomineeKeypair.publicKey)
+      );
+
+      expect(logs).to.contain("Vesting admin does not match the provided signer");
+    });
+
+    it("fails to accept if not nominated", async () => {
+      await vesting.proposeAdmin(adminKeypair, nomineeKeypair.publicKey);
+
+      const logs = await errLogs(vesting.acceptAdmin(Keypair.generate()));
+
+      expect(logs).to.contain(
+        "Signer is not the admin nominated for this vesting account"
+      );
+    });
+
+    it("fails to accept if the nomination was cancelled", async () => {
+      await vesting.proposeAdmin(adminKeypair, nomineeKeypair.publicKey);
+      await vesting.proposeAdmin(adminKeypair, PublicKey.default);
+
+      const logs = await errLogs(vesting.acceptAdmin(nomineeKeypair));
+
+      expect(logs).to.contain(
+        "Signer is not the admin nominated for this vesting account"
+      );
+    });
+
+    it("transfers the admin role in two steps", async () => {
+      await vesting.proposeAdmin(adminKeypair, nomineeKeypair.publicKey);
+
+      let vestingInfo = await vesting.fetch();
+      expect(vestingInfo.admin).to.deep.eq(adminKeypair.publicKey);
+      expect(vestingInfo.pendingAdmin).to.deep.eq(nomineeKeypair.publicKey);
+
+      await vesting.acceptAdmin(nomineeKeypair);
+
+      vestingInfo = await vesting.fetch();
+      expect(vestingInfo.admin).to.deep.eq(nomineeKeypair.publicKey);
+      expect(vestingInfo.pendingAdmin).to.deep.eq(PublicKey.default);
+
+      // the previous admin lost their powers
+      const logs = await errLogs(
+        vesting.proposeAdmin(adminKeypair, adminKeypair.publicKey)
+      );
+      expect(logs).to.contain("Vesting admin does not match the provided signer");
+    });
+
+    it("fails to rotate a schedule of another admin", async () => {
+      const other = await Vesting.init();
+
+      const logs = await errLogs(
+        Vesting.rotateAdminBatch(adminKeypair, nomineeKeypair, [
+          vesting.id,
+          other.id,
+        ])
+      );
+
+      expect(logs).to.contain("VestingAdminMismatch");
+      expect((await vesting.fetch()).admin).to.deep.eq(adminKeypair.publicKey);
+    });
+
+    it("rotates many schedules at once", async () => {
+      const vesting2 = await Vesting.init({ adminKeypair });
+      const vesting3 = await Vesting.init({ adminKeypair });
+
+      await Vesting.rotateAdminBatch(adminKeypair, nomineeKeypair, [
+        vesting.id,
+        vesting2.id,
+        vesting3.id,
+      ]);
+
+      for (const v of [vesting, vesting2, vesting3]) {
+        expect((await v.fetch()).admin).to.deep.eq(nomineeKeypair.publicKey);
+      }
+    });
+
+    it("moves the admin listing when the nomination is accepted", async () => {
+      await vesting.proposeAdmin(adminKeypair, nomineeKeypair.publicKey);
+      await vesting.acceptAdmin(nomineeKeypair);
+
+      expect(await adminListing(adminKeypair.publicKey)).to.be.empty;
+      expect(await adminListing(nomineeKeypair.publicKey)).to.deep.eq([
+        vesting.id,
+      ]);
+    });
+
+    it("fails to accept without the registry pages", async () => {
+      await vesting.proposeAdmin(adminKeypair, nomineeKeypair.publicKey);
+
+      const logs = await errLogs(
+        program.methods
+          .acceptAdmin()
+          .accounts({
+            pendingAdmin: nomineeKeypair.publicKey,
+            vesting: vesting.id,
+          })
+          .signers([nomineeKeypair])
+          .rpc()
+      );
+
+      expect(logs).to.contain(
+        "The registry page which lists the vesting account and a page of its new owner must be passed as remaining accounts"
+      );
+    });
+
+    it("rotates and enumerates the schedules of the new admin", async () => {
+      const vesting2 = await Vesting.init({ adminKeypair });
+
+      await Vesting.rotateAdminBatch(adminKeypair, nomineeKeypair, [
+        vesting.id,
+        vesting2.id,
+      ]);
+
+      expect(await adminListing(adminKeypair.publicKey)).to.be.empty;
+      expect(await adminListing(nomineeKeypair.publicKey)).to.have.deep.members(
+        [vesting.id, vesting2.id]
+      );
+
+      // the schedules can be rotated back with the pages of the new admin
+      await Vesting.rotateAdminBatch(nomineeKeypair, adminKeypair, [
+        vesting2.id,
+      ]);
+
+      expect(await adminListing(adminKeypair.publicKey)).to.deep.eq([
+        vesting2.id,
+      ]);
+      expect(await adminListing(nomineeKeypair.publicKey)).to.deep.eq([
+        vesting.id,
+      ]);
+    });
+
+    async function adminListing(admin: PublicKey): Promise<PublicKey[]> {
+      const info = await program.account.vestingRegistry.fetchNullable(
+        Vesting.registryFrom("admin", admin, 0)
+      );
+      return info?.schedules ?? [];
+    }
+  });
+}
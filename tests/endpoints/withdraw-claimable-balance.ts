@@ -0,0 +1,141 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createMint,
+  createAccount,
+  freezeAccount,
+  getAccount,
+  mintTo,
+  thawAccount,
+} from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("withdraw_claimable_balance", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create freezable mint and vestee wallet", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        payer.publicKey,
+        9
+      );
+
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+    });
+
+    beforeEach("create and fund vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        immediateVesteeWalletChange: true,
+      });
+
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+    });
+
+    const freezeVesteeWallet = () =>
+      freezeAccount(
+        provider.connection,
+        payer,
+        vesteeWallet,
+        vestingMint,
+        payer
+      );
+
+    it("fails if there is no claimable balance", async () => {
+      const logs = await errLogs(
+        vesting.withdrawClaimableBalance({ vesteeWallet })
+      );
+
+      expect(logs).to.contain("There is no claimable balance to withdraw");
+    });
+
+    it("escrows withdrawals to a frozen vestee wallet", async () => {
+      await freezeVesteeWallet();
+
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 10);
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 20);
+
+      const vestingInfo = await vesting.fetch();
+      expect(vestingInfo.claimableBalance.amount.toNumber()).to.eq(30);
+      expect(vestingInfo.cumulativeWithdrawn.amount.toNumber()).to.eq(30);
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(70);
+
+      const vault = await getAccount(provider.connection, vesting.vault);
+      expect(Number(vault.amount)).to.eq(100);
+
+      const logs = await errLogs(
+        vesting.withdrawClaimableBalance({ vesteeWallet })
+      );
+      expect(logs).to.contain("The vestee wallet cannot receive tokens");
+
+      await thawAccount(
+        provider.connection,
+        payer,
+        vesteeWallet,
+        vestingMint,
+        payer
+      );
+      await vesting.withdrawClaimableBalance({ vesteeWallet });
+
+      const wallet = await getAccount(provider.connection, vesteeWallet);
+      expect(Number(wallet.amount)).to.eq(30);
+      expect(
+        (await vesting.fetch()).claimableBalance.amount.toNumber()
+      ).to.eq(0);
+    });
+
+    it("pulls the claimable balance to a new vestee wallet", async () => {
+      await freezeVesteeWallet();
+      await vesting.withdrawMaxVestedTokens({ vesteeWallet });
+
+      // the escrowed tokens are not part of the vault balance
+      await vesting.syncVault();
+      expect((await vesting.fetch()).vaultBalance.amount.toNumber()).to.eq(0);
+
+      const vesteeWalletNew = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+      await vesting.requestVesteeWalletChange(vesteeWalletNew);
+      await vesting.withdrawClaimableBalance({ vesteeWallet: vesteeWalletNew });
+
+      const wallet = await getAccount(provider.connection, vesteeWalletNew);
+      expect(Number(wallet.amount)).to.eq(100);
+    });
+  });
+}
@@ -0,0 +1,125 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("withdraw_max_vested_tokens", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    let vesteeWallet: PublicKey;
+    let fundingWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create vesting mint", async () => {
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+    });
+
+    beforeEach("create vestee wallet", async () => {
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        payer.publicKey,
+        Keypair.generate()
+      );
+    });
+
+    beforeEach("create vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+      });
+    });
+
+    beforeEach("create funding wallet", async () => {
+      fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+    });
+
+    it("withdraws the vault balance if partially funded", async () => {
+      // No need to update vested tokens before, the withdrawal accrues them
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+      await vesting.withdrawMaxVestedTokens({ vesteeWallet });
+
+      const vestingInfo = await vesting.fetch();
+      const vestedAmount = vestingInfo.cumulativeVested.amount.toNumber();
+
+      expect(vestedAmount).to.be.above(100);
+      expect(vestingInfo.cumulativeWithdrawn.amount.toNumber()).to.eq(100);
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(0);
+      expect(vestingInfo.unfundedLiability.amount.toNumber()).to.eq(
+        vestedAmount - 100
+      );
+
+      const walletInfo = await getAccount(provider.connection, vesteeWallet);
+      expect(Number(walletInfo.amount)).to.eq(100);
+    });
+
+    it("withdraws the liability if overfunded", async () => {
+      await vesting.fundVestingVault(
+        { walletAuthority, fundingWallet },
+        10_000
+      );
+      await vesting.withdrawMaxVestedTokens({ vesteeWallet });
+
+      const vestingInfo = await vesting.fetch();
+      const vestedAmount = vestingInfo.cumulativeVested.amount.toNumber();
+
+      expect(vestedAmount).to.be.above(0);
+      expect(vestingInfo.cumulativeWithdrawn.amount.toNumber()).to.eq(
+        vestedAmount
+      );
+      expect(vestingInfo.vaultBalance.amount.toNumber()).to.eq(
+        10_000 - vestedAmount
+      );
+
+      const walletInfo = await getAccount(provider.connection, vesteeWallet);
+      expect(Number(walletInfo.amount)).to.eq(vestedAmount);
+
+      // Nothing more to withdraw, the call succeeds with zero tokens, but
+      // neither transfers nor emits an event
+      const signature = await vesting.withdrawMaxVestedTokens({
+        vesteeWallet,
+      });
+      const vestingInfoAfter = await vesting.fetch();
+      expect(vestingInfoAfter.cumulativeWithdrawn.amount.toNumber()).to.eq(
+        vestedAmount
+      );
+
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+      });
+      const logs = tx.meta.logMessages.join("\n");
+      expect(logs).to.contain("There are no vested tokens to withdraw");
+      expect(logs).not.to.contain("Program data:");
+    });
+  });
+}
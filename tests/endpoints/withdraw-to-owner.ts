@@ -0,0 +1,149 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createMint,
+  createAccount,
+  getAccount,
+  getAssociatedTokenAddress,
+  mintTo,
+} from "@solana/spl-token";
+import { errLogs, provider, payer } from "../helpers";
+import { Vesting } from "../vesting";
+
+export function test() {
+  describe("withdraw_vested_tokens to the beneficiary owner", () => {
+    const adminKeypair = Keypair.generate();
+    const walletAuthority = Keypair.generate();
+    let vesteeOwner: Keypair;
+    let vesteeWallet: PublicKey;
+    let vestingMint: PublicKey;
+    let vesting: Vesting;
+
+    beforeEach("create vesting mint and vestee wallet", async () => {
+      vesteeOwner = Keypair.generate();
+      vestingMint = await createMint(
+        provider.connection,
+        payer,
+        payer.publicKey,
+        null,
+        9
+      );
+
+      vesteeWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        vesteeOwner.publicKey,
+        Keypair.generate()
+      );
+    });
+
+    beforeEach("create and fund vesting account", async () => {
+      vesting = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        withdrawToOwner: true,
+      });
+
+      const fundingWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        walletAuthority.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer,
+        vestingMint,
+        fundingWallet,
+        payer.publicKey,
+        1_000_000
+      );
+
+      await vesting.updateVestedTokens();
+      await vesting.fundVestingVault({ walletAuthority, fundingWallet }, 100);
+    });
+
+    it("records the owner of the vestee wallet", async () => {
+      const vestingInfo = await vesting.fetch();
+
+      expect(vestingInfo.beneficiaryOwner).to.deep.eq(vesteeOwner.publicKey);
+      expect(vestingInfo.vesteeWallet).to.deep.eq(vesteeWallet);
+    });
+
+    it("withdraws to another token account of the owner", async () => {
+      const otherWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        vesteeOwner.publicKey,
+        Keypair.generate()
+      );
+
+      await vesting.withdrawVestedTokens({ vesteeWallet: otherWallet }, 10);
+
+      const wallet = await getAccount(provider.connection, otherWallet);
+      expect(Number(wallet.amount)).to.eq(10);
+    });
+
+    it("fails if the token account belongs to someone else", async () => {
+      const strangerWallet = await createAccount(
+        provider.connection,
+        payer,
+        vestingMint,
+        Keypair.generate().publicKey
+      );
+
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens({ vesteeWallet: strangerWallet }, 10)
+      );
+
+      expect(logs).to.contain(
+        "Only the vestee wallet or the associated token account of the beneficiary owner can escrow withdrawals"
+      );
+      const wallet = await getAccount(provider.connection, strangerWallet);
+      expect(Number(wallet.amount)).to.eq(0);
+      expect(
+        (await vesting.fetch()).claimableBalance.amount.toNumber()
+      ).to.eq(0);
+    });
+
+    it("creates the associated token account of the owner", async () => {
+      const associatedWallet = await getAssociatedTokenAddress(
+        vestingMint,
+        vesteeOwner.publicKey
+      );
+
+      // without the accounts to create it, the tokens are escrowed
+      await vesting.withdrawVestedTokens({ vesteeWallet: associatedWallet }, 10);
+      expect(await provider.connection.getAccountInfo(associatedWallet)).to.be
+        .null;
+
+      await vesting.withdrawMaxVestedTokens({
+        vesteeWallet: associatedWallet,
+        createVesteeWallet: true,
+      });
+
+      const wallet = await getAccount(provider.connection, associatedWallet);
+      expect(wallet.owner).to.deep.eq(vesteeOwner.publicKey);
+      expect(Number(wallet.amount)).to.eq(90);
+    });
+
+    it("fails to create a token account at another address", async () => {
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens(
+          {
+            vesteeWallet: Keypair.generate().publicKey,
+            createVesteeWallet: true,
+          },
+          10
+        )
+      );
+
+      expect(logs).to.contain(
+        "A vestee wallet which does not exist yet must be the associated token account of the beneficiary owner"
+      );
+    });
+  });
+}
@@ -114,6 +114,74 @@ export function test() {
       );
     });
 
+    it("fails during a blackout window", async () => {
+      await vesting.updateVestedTokens();
+      const vestingInfo = await vesting.fetch();
+      const vestedAmount = vestingInfo.cumulativeVested.amount.toNumber();
+
+      await vesting.fundVestingVault(
+        { walletAuthority, fundingWallet },
+        vestedAmount
+      );
+
+      const now = Math.floor(Date.now() / 1000);
+      await vesting.setBlackoutWindows({ adminKeypair }, [
+        { start: now - 3600, end: now + 3600 },
+      ]);
+
+      const logs = await errLogs(
+        vesting.withdrawVestedTokens({ vesteeWallet }, 10)
+      );
+
+      expect(logs).to.contain(
+        "Withdrawals are blocked during an active blackout window"
+      );
+
+      await vesting.setBlackoutWindows({ adminKeypair }, []);
+      await vesting.withdrawVestedTokens({ vesteeWallet }, 10);
+
+      const vestingInfoAfter = await vesting.fetch();
+      expect(vestingInfoAfter.cumulativeWithdrawn.amount.toNumber()).to.eq(10);
+    });
+
+    it("fails if rate limit is exceeded", async () => {
+      const vesting2 = await Vesting.init({
+        adminKeypair,
+        vesteeWallet,
+        mint: vestingMint,
+        // 7 days
+        rateLimitWindowSecs: 604_800,
+        rateLimitMaxAmount: 100,
+      });
+
+      await vesting2.updateVestedTokens();
+      await vesting2.fundVestingVault(
+        { walletAuthority, fundingWallet },
+        1_000
+      );
+
+      await vesting2.withdrawVestedTokens({ vesteeWallet }, 60);
+
+      const logs = await errLogs(
+        vesting2.withdrawVestedTokens({ vesteeWallet }, 41)
+      );
+      expect(logs).to.contain(
+        "Withdrawal exceeds the rate limit of this vesting schedule"
+      );
+
+      await vesting2.withdrawVestedTokens({ vesteeWallet }, 40);
+
+      const vestingInfo = await vesting2.fetch();
+      expect(vestingInfo.cumulativeWithdrawn.amount.toNumber()).to.eq(100);
+      const rateLimitWithdrawn = vestingInfo.rateLimitBuckets.reduce(
+        (sum, bucket) => sum + bucket.amount.toNumber(),
+        0
+      );
+      expect(rateLimitWithdrawn).to.eq(100);
+      expect(vestingInfo.rateLimit.windowSecs.toNumber()).to.eq(604_800);
+      expect(vestingInfo.rateLimit.maxAmount.amount.toNumber()).to.eq(100);
+    });
+
     it("works", async () => {
       await vesting.updateVestedTokens();
       const vestingInfoBefore = await vesting.fetch();
@@ -4,6 +4,7 @@ import NodeWallet from "@project-serum/anchor/dist/cjs/nodewallet";
 import { expect } from "chai";
 import { Program, workspace } from "@project-serum/anchor";
 import { VestingTreasury } from "../target/types/vesting_treasury";
+import { DaoStub } from "../target/types/dao_stub";
 
 export const provider = AnchorProvider.local();
 setProvider(provider);
@@ -11,6 +12,9 @@ export const payer = (provider.wallet as NodeWallet).payer;
 
 export const vesting = workspace.VestingTreasury as Program<VestingTreasury>;
 
+// reference program which administers vesting schedules over CPI
+export const daoStub = workspace.DaoStub as Program<DaoStub>;
+
 export async function errLogs(job: Promise<unknown>): Promise<string> {
   try {
     await job;
@@ -1,21 +1,63 @@
 import chai from "chai";
 
 import * as createVestingSchedule from "./endpoints/create-vesting-schedule";
+import * as createVestingScheduleV2 from "./endpoints/create-vesting-schedule-v2";
+import * as createDeterministicVestingSchedule from "./endpoints/create-deterministic-vesting-schedule";
+import * as createAndFundVestingSchedule from "./endpoints/create-and-fund-vesting-schedule";
 import * as changeVestingWallet from "./endpoints/change-vestee-wallet";
 import * as updateVestedTokens from "./endpoints/update-vested-tokens";
 import * as fundVestingVault from "./endpoints/fund-vesting-vault";
 import * as withdrawVestedTokens from "./endpoints/withdraw-vested-tokens";
+import * as withdrawMaxVestedTokens from "./endpoints/withdraw-max-vested-tokens";
+import * as withdrawToOwner from "./endpoints/withdraw-to-owner";
+import * as withdrawClaimableBalance from "./endpoints/withdraw-claimable-balance";
 import * as closeVestingSchedule from "./endpoints/close-vesting-schedule";
+import * as setBlackoutWindows from "./endpoints/set-blackout-windows";
+import * as sweepUnclaimedTokens from "./endpoints/sweep-unclaimed-tokens";
+import * as setWithdrawalDelegate from "./endpoints/set-withdrawal-delegate";
+import * as createRegistryPage from "./endpoints/create-registry-page";
+import * as migrateVesting from "./endpoints/migrate-vesting";
+import * as syncVault from "./endpoints/sync-vault";
+import * as refundSurplus from "./endpoints/refund-surplus";
+import * as setRoles from "./endpoints/set-roles";
+import * as multisig from "./endpoints/multisig";
+import * as programAdmin from "./endpoints/program-admin";
+import * as adminActionTimelock from "./endpoints/admin-action-timelock";
+import * as requestVesteeWalletChange from "./endpoints/request-vestee-wallet-change";
+import * as setVesteeOperator from "./endpoints/set-vestee-operator";
+import * as freezeVesting from "./endpoints/freeze-vesting";
+import * as transferAdmin from "./endpoints/transfer-admin";
 
 import { airdrop, provider } from "./helpers";
 
 describe("vesting-treasury", () => {
     createVestingSchedule.test();
+    createVestingScheduleV2.test();
+    createDeterministicVestingSchedule.test();
+    createAndFundVestingSchedule.test();
     changeVestingWallet.test();
     updateVestedTokens.test();
     fundVestingVault.test();
     withdrawVestedTokens.test();
+    withdrawMaxVestedTokens.test();
+    withdrawToOwner.test();
+    withdrawClaimableBalance.test();
     closeVestingSchedule.test();
+    setBlackoutWindows.test();
+    sweepUnclaimedTokens.test();
+    setWithdrawalDelegate.test();
+    createRegistryPage.test();
+    migrateVesting.test();
+    syncVault.test();
+    refundSurplus.test();
+    setRoles.test();
+    multisig.test();
+    programAdmin.test();
+    adminActionTimelock.test();
+    requestVesteeWalletChange.test();
+    setVesteeOperator.test();
+    freezeVesting.test();
+    transferAdmin.test();
 
 
   before("airdrop SOL to provider wallet", async () => {
@@ -0,0 +1,159 @@
+import { vesting, payer } from "./helpers";
+import {
+  AccountMeta,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+
+export class Multisig {
+  constructor(
+    public keypair: Keypair,
+    public members: Keypair[],
+    public signer: PublicKey
+  ) {
+    //
+  }
+
+  public get id(): PublicKey {
+    return this.keypair.publicKey;
+  }
+
+  public static async init(
+    members: Keypair[],
+    threshold: number
+  ): Promise<Multisig> {
+    const multisigKeypair = Keypair.generate();
+
+    await vesting.methods
+      .createMultisig(
+        members.map((m) => m.publicKey),
+        threshold
+      )
+      .accounts({
+        payer: payer.publicKey,
+        multisig: multisigKeypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([multisigKeypair])
+      .rpc();
+
+    return new Multisig(
+      multisigKeypair,
+      members,
+      Multisig.signerFrom(multisigKeypair.publicKey)
+    );
+  }
+
+  public static signerFrom(multisig: PublicKey): PublicKey {
+    const [pda, _bumpSeed] = PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig"), multisig.toBytes()],
+      vesting.programId
+    );
+    return pda;
+  }
+
+  public async fetch() {
+    return vesting.account.multisig.fetch(this.id);
+  }
+
+  // the accounts of the instruction as stored in a proposal
+  public static accountsOf(ix: TransactionInstruction) {
+    return ix.keys.map(({ pubkey, isSigner, isWritable }) => ({
+      pubkey,
+      isSigner,
+      isWritable,
+    }));
+  }
+
+  // the accounts of the instruction as passed to the outer transaction, in
+  // which the multisig signer cannot sign
+  public remainingAccountsOf(ix: TransactionInstruction): AccountMeta[] {
+    return [
+      ...ix.keys.map(({ pubkey, isSigner, isWritable }) => ({
+        pubkey,
+        isSigner: isSigner && !pubkey.equals(this.signer),
+        isWritable,
+      })),
+      { pubkey: vesting.programId, isSigner: false, isWritable: false },
+    ];
+  }
+
+  // invokes the instruction with the signatures of the given members in the
+  // same transaction
+  public async invoke(ix: TransactionInstruction, members: Keypair[]) {
+    await vesting.methods
+      .invokeMultisigAction(Multisig.accountsOf(ix), ix.data)
+      .accounts({
+        multisig: this.id,
+        multisigSigner: this.signer,
+      })
+      .remainingAccounts([
+        ...this.remainingAccountsOf(ix),
+        ...members.map((m) => ({
+          pubkey: m.publicKey,
+          isSigner: true,
+          isWritable: false,
+        })),
+      ])
+      .signers(members)
+      .rpc();
+  }
+
+  public async propose(
+    ix: TransactionInstruction,
+    proposer: Keypair
+  ): Promise<PublicKey> {
+    const proposalKeypair = Keypair.generate();
+
+    await vesting.methods
+      .proposeMultisigAction(Multisig.accountsOf(ix), ix.data)
+      .accounts({
+        proposer: proposer.publicKey,
+        multisig: this.id,
+        proposal: proposalKeypair.publicKey,
+        payer: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([proposer, proposalKeypair])
+      .rpc();
+
+    return proposalKeypair.publicKey;
+  }
+
+  public async approve(proposal: PublicKey, member: Keypair) {
+    await vesting.methods
+      .approveMultisigAction()
+      .accounts({
+        member: member.publicKey,
+        multisig: this.id,
+        proposal,
+      })
+      .signers([member])
+      .rpc();
+  }
+
+  public async execute(proposal: PublicKey, ix: TransactionInstruction) {
+    await vesting.methods
+      .executeMultisigAction()
+      .accounts({
+        multisig: this.id,
+        multisigSigner: this.signer,
+        proposal,
+        rentReceiver: payer.publicKey,
+      })
+      .remainingAccounts(this.remainingAccountsOf(ix))
+      .rpc();
+  }
+
+  public async cancel(proposal: PublicKey) {
+    await vesting.methods
+      .cancelMultisigAction()
+      .accounts({
+        payer: payer.publicKey,
+        proposal,
+      })
+      .rpc();
+  }
+}
@@ -0,0 +1,179 @@
+//! A reference program which stands in for a DAO, such as SPL governance,
+//! that administers vesting schedules over CPI. Its governance PDA, derived
+//! from [`GOVERNANCE_PREFIX`], is the admin of the schedules it creates and
+//! signs the admin endpoints of the vesting treasury. Being a PDA it holds
+//! no lamports, hence a separate payer sponsors the rent of new schedules and
+//! a separate rent receiver gets it back when they are closed.
+//!
+//! The registry pages of the schedules are passed through as remaining
+//! accounts.
+//!
+//! A real DAO would only sign once a proposal passed, the stub signs for
+//! anyone.
+
+use anchor_lang::prelude::*;
+use vesting_treasury::cpi::accounts::{
+    ChangeVesteeWallet, CloseVestingSchedule, CreateVestingSchedule,
+};
+use vesting_treasury::models::{TimeStamp, TokenAmount, VestingOptions};
+use vesting_treasury::program::VestingTreasury;
+
+declare_id!("Cj8RiQs2oRaC7bLni3gSz2pgnBF6a9wMC2BVTyLoacsL");
+
+pub const GOVERNANCE_PREFIX: &[u8; 10] = b"governance";
+
+#[program]
+pub mod dao_stub {
+    use super::*;
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_vesting_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateVestingScheduleViaDao<'info>>,
+        vesting_amount: TokenAmount,
+        start_ts: TimeStamp,
+        cliff_periods: u64,
+        total_periods: u64,
+        period_type: u32,
+        options: VestingOptions,
+    ) -> Result<()> {
+        let accs = ctx.accounts;
+        let bump = *ctx.bumps.get("governance").unwrap();
+        let signer_seeds: &[&[u8]] = &[GOVERNANCE_PREFIX, &[bump]];
+
+        let cpi_accounts = CreateVestingSchedule {
+            admin: accs.governance.to_account_info(),
+            payer: accs.payer.to_account_info(),
+            vesting: accs.vesting.to_account_info(),
+            vesting_signer: accs.vesting_signer.to_account_info(),
+            vesting_vault: accs.vesting_vault.to_account_info(),
+            mint: accs.mint.to_account_info(),
+            vestee_wallet: accs.vestee_wallet.to_account_info(),
+            token_program: accs.token_program.to_account_info(),
+            associated_token_program: accs.associated_token_program.to_account_info(),
+            system_program: accs.system_program.to_account_info(),
+            rent: accs.rent.to_account_info(),
+        };
+
+        vesting_treasury::cpi::create_vesting_schedule(
+            CpiContext::new(accs.vesting_treasury.to_account_info(), cpi_accounts)
+                .with_signer(&[signer_seeds])
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            vesting_amount,
+            start_ts,
+            cliff_periods,
+            total_periods,
+            period_type,
+            options,
+        )
+    }
+
+    pub fn change_vestee_wallet(ctx: Context<ChangeVesteeWalletViaDao>) -> Result<()> {
+        let accs = ctx.accounts;
+        let bump = *ctx.bumps.get("governance").unwrap();
+        let signer_seeds: &[&[u8]] = &[GOVERNANCE_PREFIX, &[bump]];
+
+        let cpi_accounts = ChangeVesteeWallet {
+            wallet_manager: accs.governance.to_account_info(),
+            vesting: accs.vesting.to_account_info(),
+            vestee_wallet_new: accs.vestee_wallet_new.to_account_info(),
+        };
+
+        vesting_treasury::cpi::change_vestee_wallet(
+            CpiContext::new(accs.vesting_treasury.to_account_info(), cpi_accounts)
+                .with_signer(&[signer_seeds]),
+        )
+    }
+
+    pub fn close_vesting_schedule<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseVestingScheduleViaDao<'info>>,
+    ) -> Result<()> {
+        let accs = ctx.accounts;
+        let bump = *ctx.bumps.get("governance").unwrap();
+        let signer_seeds: &[&[u8]] = &[GOVERNANCE_PREFIX, &[bump]];
+
+        let cpi_accounts = CloseVestingSchedule {
+            closer: accs.governance.to_account_info(),
+            vesting: accs.vesting.to_account_info(),
+            vesting_vault: accs.vesting_vault.to_account_info(),
+            vesting_signer: accs.vesting_signer.to_account_info(),
+            recovery_wallet: accs.recovery_wallet.to_account_info(),
+            rent_receiver: accs.rent_receiver.to_account_info(),
+            token_program: accs.token_program.to_account_info(),
+        };
+
+        vesting_treasury::cpi::close_vesting_schedule(
+            CpiContext::new(accs.vesting_treasury.to_account_info(), cpi_accounts)
+                .with_signer(&[signer_seeds])
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        )
+    }
+}
+
+// The vesting treasury validates the accounts which are passed through.
+
+#[derive(Accounts)]
+pub struct CreateVestingScheduleViaDao<'info> {
+    /// CHECK: The governance PDA which signs as the admin.
+    #[account(seeds = [GOVERNANCE_PREFIX], bump)]
+    pub governance: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub vesting: Signer<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub vesting_signer: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    #[account(mut)]
+    pub vesting_vault: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub mint: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub vestee_wallet: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub associated_token_program: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub system_program: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub rent: AccountInfo<'info>,
+    pub vesting_treasury: Program<'info, VestingTreasury>,
+}
+
+#[derive(Accounts)]
+pub struct ChangeVesteeWalletViaDao<'info> {
+    /// CHECK: The governance PDA which signs as the wallet manager.
+    #[account(seeds = [GOVERNANCE_PREFIX], bump)]
+    pub governance: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    #[account(mut)]
+    pub vesting: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub vestee_wallet_new: AccountInfo<'info>,
+    pub vesting_treasury: Program<'info, VestingTreasury>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVestingScheduleViaDao<'info> {
+    /// CHECK: The governance PDA which signs as the closer.
+    #[account(seeds = [GOVERNANCE_PREFIX], bump)]
+    pub governance: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    #[account(mut)]
+    pub vesting: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    #[account(mut)]
+    pub vesting_vault: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub vesting_signer: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    #[account(mut)]
+    pub recovery_wallet: AccountInfo<'info>,
+    /// CHECK: Gets the rent of the closed accounts, as the governance PDA
+    /// should not hold lamports.
+    #[account(mut)]
+    pub rent_receiver: AccountInfo<'info>,
+    /// CHECK: Passed through to the vesting treasury.
+    pub token_program: AccountInfo<'info>,
+    pub vesting_treasury: Program<'info, VestingTreasury>,
+}
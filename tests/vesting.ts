@@ -1,5 +1,12 @@
 import { vesting, payer, provider, airdrop } from "./helpers";
-import { Keypair, PublicKey, SystemProgram, SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import {
+  AccountMeta,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
 import {
   createAccount,
   createMint,
@@ -8,6 +15,8 @@ import {
   Account,
   getAccount,
   getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { BN } from "@project-serum/anchor";
@@ -26,6 +35,29 @@ export interface InitVestingArgs {
   cliffPeriods: number;
   totalPeriods: number;
   periodType: number;
+  claimDeadline: number;
+  beneficiaryOnly: boolean;
+  rateLimitWindowSecs: number;
+  rateLimitMaxAmount: number;
+  associatedVault: boolean;
+  roles: Partial<VestingRoles>;
+  actionDelaySecs: number;
+  guardian: PublicKey;
+  immediateVesteeWalletChange: boolean;
+  withdrawToOwner: boolean;
+  // creates the schedule with the typed parameters of the v2 endpoint
+  v2: boolean;
+  // creates the schedule without listing it in registry pages
+  skipRegistry: boolean;
+}
+
+export interface InitAndFundVestingArgs extends InitVestingArgs {
+  walletAuthority: Keypair;
+  fundingWallet: PublicKey;
+}
+
+export interface InitDeterministicVestingArgs extends InitVestingArgs {
+  grantId: number;
 }
 
 export interface ChangeVesteeWalletArgs {
@@ -53,23 +85,91 @@ export interface WithdrawVestedTokens{
   vestingVault: PublicKey;
   pda: PublicKey;
   vesteeWallet: PublicKey;
+  // signs in the beneficiary only mode, passed as the first remaining
+  // account
+  withdrawAuthority: Keypair;
+  // passes the accounts needed to create the associated token account of
+  // the beneficiary owner as the vestee wallet
+  createVesteeWallet: boolean;
 }
 
 export interface CloseVestingSchedule{
   adminKeypair: Keypair;
   vestingKeypair: Keypair;
   skipAdminSignature: boolean;
+  recoveryWallet: PublicKey;
+  rentReceiver: PublicKey;
+}
+
+export interface SetBlackoutWindows {
+  adminKeypair: Keypair;
+  vestingKeypair: Keypair;
+  skipAdminSignature: boolean;
+}
+
+export interface SweepUnclaimedTokens {
+  adminKeypair: Keypair;
+  vestingKeypair: Keypair;
+  vestingVault: PublicKey;
+  pda: PublicKey;
+  recoveryWallet: PublicKey;
+  skipAdminSignature: boolean;
+}
+
+export interface RefundSurplus {
+  adminKeypair: Keypair;
+  refundWallet: PublicKey;
+}
+
+export interface SetWithdrawalDelegate {
+  vesteeKeypair: Keypair;
+  vestingKeypair: Keypair;
+}
+
+export interface VestingRoles {
+  funder: PublicKey;
+  walletManager: PublicKey;
+  revoker: PublicKey;
+  closer: PublicKey;
+}
+
+export type RegistryKind = "admin" | "vestee";
+
+// must match the max number of schedules per registry page in the program
+export const MAX_SCHEDULES_PER_PAGE = 32;
+
+export interface BlackoutWindow {
+  start: number;
+  end: number;
+}
+
+// must match the permission bits of the vestee operators in the program
+export const OPERATOR_WITHDRAW = 1 << 0;
+export const OPERATOR_CANCEL_ADMIN_ACTION = 1 << 1;
+
+export interface VesteeOperator {
+  key: PublicKey;
+  permissions: number;
+  // unix timestamp, zero means never
+  expiresAt: number;
 }
 
 export class Vesting {
+  // owner of the vestee wallet at creation, whose registry lists the vesting
+  public vesteeOwner: PublicKey;
+  // the vault chosen at creation, which depends on the vault layout
+  public vault: PublicKey;
+
   public get id(): PublicKey {
-    return this.keypair.publicKey;
+    return this.address ?? this.keypair.publicKey;
   }
 
   private constructor(
     public keypair: Keypair,
     public admin: Keypair,
-    public mint: PublicKey
+    public mint: PublicKey,
+    // set if the vesting account lives at a PDA rather than at a keypair
+    private address?: PublicKey
   ) {
     //
   }
@@ -77,8 +177,8 @@ export class Vesting {
   public static async init(
     input: Partial<InitVestingArgs> = {},
     ): Promise<Vesting> {
+    // the admin needs no lamports as the provider wallet pays the rent
     const adminKeypair = input.adminKeypair ?? Keypair.generate();
-    await airdrop(adminKeypair.publicKey);
     const vestingKeypair = input.keypair ?? Keypair.generate();
     const skipAdminSignature = input.skipAdminSignature ?? false;
     const skipKeypairSignature = input.skipKeypairSignature ?? false;
@@ -104,13 +204,11 @@ export class Vesting {
 
     const vestingVault =
       input.vestingVault ??
-      (await (async () => {
-        const [pda, _bumpSeed] = PublicKey.findProgramAddressSync(
-          [Buffer.from("vault"), vestingKeypair.publicKey.toBytes()],
-          vesting.programId
-        );
-        return pda;
-    })());
+      (await Vesting.vaultFrom(
+        vestingKeypair.publicKey,
+        mint,
+        input.associatedVault ?? false
+      ));
 
     const vesteeWallet = input.vesteeWallet ??
       (await (async () => {
@@ -124,12 +222,6 @@ export class Vesting {
     })());
 
 
-    const vestingAmount = input.vestingAmount ?? 10_000;
-    const startTs = input.startTs ?? 1577836801; // Jan 01 2020
-    const cliffPeriods = input.cliffPeriods ?? 12;
-    const totalPeriods = input.totalPeriods ?? 48;
-    const periodType = input.periodType ?? 2; // Monthly
-
     const signers = [];
     if (!skipAdminSignature) {
       signers.push(adminKeypair);
@@ -139,27 +231,417 @@ export class Vesting {
       signers.push(vestingKeypair);
     }
 
+    const vesteeOwner = await Vesting.walletOwnerOrPayer(vesteeWallet);
+
+    const [vestingAmount, startTs, cliffPeriods, totalPeriods, periodType, options] =
+      Vesting.scheduleArgs(input);
+    const method = input.v2
+      ? vesting.methods.createVestingScheduleV2(
+          {
+            vestingAmount,
+            startTs,
+            cliffPeriods,
+            totalPeriods,
+            periodType: Vesting.periodTypeFrom(periodType),
+          },
+          options
+        )
+      : vesting.methods.createVestingSchedule(...Vesting.scheduleArgs(input));
+
+    await method
+      .accounts({
+        admin: adminKeypair.publicKey,
+        payer: payer.publicKey,
+        vesting: vestingKeypair.publicKey,
+        vestingSigner: vestingSignerPda,
+        mint,
+        vestingVault,
+        vesteeWallet,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await Vesting.registryAccountsWithRoom(
+          input.skipRegistry ?? false,
+          adminKeypair.publicKey,
+          vesteeOwner
+        )
+      )
+      .signers(signers)
+      .rpc();
+
+    const v = new Vesting(vestingKeypair, adminKeypair, mint);
+    v.vesteeOwner = vesteeOwner;
+    v.vault = vestingVault;
+    return v;
+  }
+
+  public static async initAndFund(
+    input: Partial<InitAndFundVestingArgs> = {},
+    fundingAmount: number,
+    ): Promise<Vesting> {
+    const adminKeypair = input.adminKeypair ?? Keypair.generate();
+    const vestingKeypair = input.keypair ?? Keypair.generate();
+    const walletAuthority = input.walletAuthority ?? adminKeypair;
+
+    const mint =
+      input.mint ??
+      (await createMint(
+        provider.connection,
+        payer,
+        adminKeypair.publicKey,
+        null,
+        6
+      ));
+
+    const vesteeWallet = input.vesteeWallet ??
+      (await createAccount(provider.connection, payer, mint, payer.publicKey));
+
+    const fundingWallet = input.fundingWallet ??
+      (await createAccount(
+        provider.connection,
+        payer,
+        mint,
+        walletAuthority.publicKey
+      ));
+
+    const [vestingSignerPda, _] = await Vesting.signerFrom(
+      vestingKeypair.publicKey
+    );
+    const vestingVault = await Vesting.vaultFrom(
+      vestingKeypair.publicKey,
+      mint,
+      input.associatedVault ?? false
+    );
+    const vesteeOwner = await Vesting.walletOwnerOrPayer(vesteeWallet);
+
+    const signers = [adminKeypair, vestingKeypair];
+    if (walletAuthority !== adminKeypair) {
+      signers.push(walletAuthority);
+    }
+
     await vesting.methods
-      .createVestingSchedule(
-        {amount: new BN(vestingAmount)},
-        {time: new BN(startTs)},
-        new BN(cliffPeriods),
-        new BN(totalPeriods),
-        periodType,
+      .createAndFundVestingSchedule(
+        ...Vesting.scheduleArgs(input),
+        {amount: new BN(fundingAmount)}
       )
       .accounts({
         admin: adminKeypair.publicKey,
+        payer: payer.publicKey,
         vesting: vestingKeypair.publicKey,
         vestingSigner: vestingSignerPda,
         mint,
         vestingVault,
         vesteeWallet,
+        walletAuthority: walletAuthority.publicKey,
+        fundingWallet,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(
+        await Vesting.registryAccountsWithRoom(
+          input.skipRegistry ?? false,
+          adminKeypair.publicKey,
+          vesteeOwner
+        )
+      )
       .signers(signers)
       .rpc();
 
-    return new Vesting(vestingKeypair, adminKeypair, mint);
+    const v = new Vesting(vestingKeypair, adminKeypair, mint);
+    v.vesteeOwner = vesteeOwner;
+    v.vault = vestingVault;
+    return v;
+  }
+
+  public static async initDeterministic(
+    input: Partial<InitDeterministicVestingArgs> = {},
+    ): Promise<Vesting> {
+    const adminKeypair = input.adminKeypair ?? Keypair.generate();
+    const grantId = input.grantId ?? 0;
+
+    const mint =
+      input.mint ??
+      (await createMint(
+        provider.connection,
+        payer,
+        adminKeypair.publicKey,
+        null,
+        6
+      ));
+
+    const vesteeWallet = input.vesteeWallet ??
+      (await createAccount(provider.connection, payer, mint, payer.publicKey));
+
+    const vestingId = Vesting.grantFrom(
+      adminKeypair.publicKey,
+      mint,
+      vesteeWallet,
+      grantId
+    );
+    const [vestingSignerPda, _] = await Vesting.signerFrom(vestingId);
+    const vestingVault = await Vesting.vaultFrom(
+      vestingId,
+      mint,
+      input.associatedVault ?? false
+    );
+    const vesteeOwner = await Vesting.walletOwnerOrPayer(vesteeWallet);
+
+    await vesting.methods
+      .createDeterministicVestingSchedule(
+        new BN(grantId),
+        ...Vesting.scheduleArgs(input)
+      )
+      .accounts({
+        admin: adminKeypair.publicKey,
+        payer: payer.publicKey,
+        vesting: vestingId,
+        vestingSigner: vestingSignerPda,
+        mint,
+        vestingVault,
+        vesteeWallet,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await Vesting.registryAccountsWithRoom(
+          input.skipRegistry ?? false,
+          adminKeypair.publicKey,
+          vesteeOwner
+        )
+      )
+      .signers([adminKeypair])
+      .rpc();
+
+    const v = new Vesting(null, adminKeypair, mint, vestingId);
+    v.vesteeOwner = vesteeOwner;
+    v.vault = vestingVault;
+    return v;
+  }
+
+  public static grantFrom(
+    admin: PublicKey,
+    mint: PublicKey,
+    vesteeWallet: PublicKey,
+    grantId: number
+  ): PublicKey {
+    const [pda, _bumpSeed] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("grant"),
+        admin.toBytes(),
+        mint.toBytes(),
+        vesteeWallet.toBytes(),
+        new BN(grantId).toArrayLike(Buffer, "le", 8),
+      ],
+      vesting.programId
+    );
+    return pda;
+  }
+
+  public static registryFrom(
+    kind: RegistryKind,
+    owner: PublicKey,
+    page: number
+  ): PublicKey {
+    const [pda, _bumpSeed] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("registry"),
+        Buffer.from(kind),
+        owner.toBytes(),
+        new BN(page).toArrayLike(Buffer, "le", 4),
+      ],
+      vesting.programId
+    );
+    return pda;
+  }
+
+  public static async createRegistryPage(
+    kind: RegistryKind,
+    owner: PublicKey,
+    page: number
+  ): Promise<PublicKey> {
+    await vesting.methods
+      .createRegistryPage({ [kind]: {} } as any, owner, page)
+      .accounts({
+        payer: payer.publicKey,
+        registry: Vesting.registryFrom(kind, owner, page),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    return Vesting.registryFrom(kind, owner, page);
+  }
+
+  // returns the first page of the registry which can list another schedule,
+  // creating a new page if all existing pages are full
+  public static async registryPageWithRoom(
+    kind: RegistryKind,
+    owner: PublicKey
+  ): Promise<PublicKey> {
+    for (let page = 0; ; page++) {
+      const registry = Vesting.registryFrom(kind, owner, page);
+      const info = await vesting.account.vestingRegistry.fetchNullable(
+        registry
+      );
+      if (info === null) {
+        return Vesting.createRegistryPage(kind, owner, page);
+      }
+      if (info.schedules.length < MAX_SCHEDULES_PER_PAGE) {
+        return registry;
+      }
+    }
+  }
+
+  // returns the page of the registry which lists this schedule, or the first
+  // page if no page lists it
+  public async registryPageOf(
+    kind: RegistryKind,
+    owner: PublicKey
+  ): Promise<PublicKey> {
+    return Vesting.registryPageListing(kind, owner, this.id);
+  }
+
+  public static async registryPageListing(
+    kind: RegistryKind,
+    owner: PublicKey,
+    vestingId: PublicKey
+  ): Promise<PublicKey> {
+    for (let page = 0; ; page++) {
+      const registry = Vesting.registryFrom(kind, owner, page);
+      const info = await vesting.account.vestingRegistry.fetchNullable(
+        registry
+      );
+      if (info === null) {
+        return Vesting.registryFrom(kind, owner, 0);
+      }
+      if (info.schedules.some((s: PublicKey) => s.equals(vestingId))) {
+        return registry;
+      }
+    }
+  }
+
+  // the registry pages in which a new schedule is listed, passed to the
+  // create endpoints as remaining accounts
+  public static async registryAccountsWithRoom(
+    skipRegistry: boolean,
+    admin: PublicKey,
+    vesteeOwner: PublicKey
+  ): Promise<AccountMeta[]> {
+    if (skipRegistry) {
+      return [];
+    }
+
+    return [
+      await Vesting.registryPageWithRoom("admin", admin),
+      await Vesting.registryPageWithRoom("vestee", vesteeOwner),
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }));
+  }
+
+  // the registry pages which list this schedule, passed to
+  // close_vesting_schedule as remaining accounts, or none if the schedule
+  // isn't listed
+  public async registryAccounts(): Promise<AccountMeta[]> {
+    const info = await this.fetch();
+    if (!info.registered) {
+      return [];
+    }
+
+    return [
+      await this.registryPageOf("admin", info.admin),
+      await this.registryPageOf("vestee", info.vesteeWalletOwner),
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }));
+  }
+
+  // the page which lists the schedule and a page of the new owner, passed as
+  // remaining accounts to the endpoints which change the admin or the owner
+  // of the vestee wallet, or none if the listing doesn't move
+  public static async registryMoveAccounts(
+    vestingId: PublicKey,
+    kind: RegistryKind,
+    newOwner: PublicKey
+  ): Promise<AccountMeta[]> {
+    const info = await vesting.account.vesting.fetch(vestingId);
+    const owner = kind === "admin" ? info.admin : info.vesteeWalletOwner;
+    if (!info.registered || owner.equals(newOwner)) {
+      return [];
+    }
+
+    return [
+      await Vesting.registryPageListing(kind, owner, vestingId),
+      await Vesting.registryPageWithRoom(kind, newOwner),
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }));
+  }
+
+  // tests pass uninitialized vestee wallets on purpose, in which case the
+  // registry of the payer is used
+  private static async walletOwnerOrPayer(
+    wallet: PublicKey
+  ): Promise<PublicKey> {
+    try {
+      return (await getAccount(provider.connection, wallet)).owner;
+    } catch {
+      return payer.publicKey;
+    }
+  }
+
+  // maps the period type as accepted by the v1 endpoint to the enum
+  public static periodTypeFrom(periodType: number): any {
+    const types = ["daily", "monthly", "quarterly", "semiAnnually", "yearly"];
+    return { [types[periodType - 1]]: {} };
+  }
+
+  // the schedule arguments and options shared by the create endpoints
+  public static scheduleArgs(
+    input: Partial<InitVestingArgs>
+  ): [{ amount: BN }, { time: BN }, BN, BN, number, any] {
+    const vestingAmount = input.vestingAmount ?? 10_000;
+    const startTs = input.startTs ?? 1577836801; // Jan 01 2020
+    const cliffPeriods = input.cliffPeriods ?? 12;
+    const totalPeriods = input.totalPeriods ?? 48;
+    const periodType = input.periodType ?? 2; // Monthly
+    const claimDeadline = input.claimDeadline ?? 0; // No deadline
+    const beneficiaryOnly = input.beneficiaryOnly ?? false;
+    const rateLimitWindowSecs = input.rateLimitWindowSecs ?? 0; // No limit
+    const rateLimitMaxAmount = input.rateLimitMaxAmount ?? 0;
+    const associatedVault = input.associatedVault ?? false;
+    const roles = Vesting.rolesFrom(input.roles ?? {});
+    const actionDelaySecs = input.actionDelaySecs ?? 0; // No timelock
+    const guardian = input.guardian ?? PublicKey.default;
+    const immediateVesteeWalletChange =
+      input.immediateVesteeWalletChange ?? false;
+    const withdrawToOwner = input.withdrawToOwner ?? false;
+
+    return [
+      {amount: new BN(vestingAmount)},
+      {time: new BN(startTs)},
+      new BN(cliffPeriods),
+      new BN(totalPeriods),
+      periodType,
+      {
+        claimDeadline: {time: new BN(claimDeadline)},
+        beneficiaryOnly,
+        rateLimit: {
+          windowSecs: new BN(rateLimitWindowSecs),
+          maxAmount: {amount: new BN(rateLimitMaxAmount)},
+        },
+        associatedVault,
+        roles,
+        actionDelaySecs: new BN(actionDelaySecs),
+        guardian,
+        immediateVesteeWalletChange,
+        withdrawToOwner,
+      },
+    ];
+  }
+
+  // unset roles are the default pubkey, which falls back to the admin
+  public static rolesFrom(roles: Partial<VestingRoles>): VestingRoles {
+    return {
+      funder: roles.funder ?? PublicKey.default,
+      walletManager: roles.walletManager ?? PublicKey.default,
+      revoker: roles.revoker ?? PublicKey.default,
+      closer: roles.closer ?? PublicKey.default,
+    };
   }
 
   public async fetch() {
@@ -167,8 +649,22 @@ export class Vesting {
   }
 
   public async vestingVault(): Promise<PublicKey> {
+    return this.vault ?? Vesting.vaultFrom(this.id, this.mint, false);
+  }
+
+  public static async vaultFrom(
+    vestingId: PublicKey,
+    mint: PublicKey,
+    associatedVault: boolean
+  ): Promise<PublicKey> {
+    if (associatedVault) {
+      const [signer, _] = await Vesting.signerFrom(vestingId);
+      // the signer is a PDA, hence off curve
+      return getAssociatedTokenAddress(mint, signer, true);
+    }
+
     const [pda, _bumpSeed] = PublicKey.findProgramAddressSync(
-      [Buffer.from("vault"), this.id.toBytes()],
+      [Buffer.from("vault"), vestingId.toBytes()],
       vesting.programId
     );
     return pda;
@@ -216,7 +712,7 @@ export class Vesting {
     const adminKeypair = input.adminKeypair ?? Keypair.generate();
     await airdrop(adminKeypair.publicKey);
 
-    const vestingKeypair = input.vestingKeypair ?? this.keypair;
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
     const skipAdminSignature = input.skipAdminSignature ?? false;
     const skipCreateVesting = input.skipCreateVesting ?? false;
 
@@ -236,26 +732,84 @@ export class Vesting {
       signers.push(adminKeypair);
     }
 
+    const registryAccounts = skipCreateVesting
+      ? []
+      : await Vesting.registryMoveAccounts(
+          vestingId,
+          "vestee",
+          await Vesting.walletOwnerOrPayer(vesteeWalletNew)
+        );
+
     await vesting.methods
       .changeVesteeWallet()
       .accounts({
-        admin: adminKeypair.publicKey,
-        vesting: vestingKeypair.publicKey,
+        walletManager: adminKeypair.publicKey,
+        vesting: vestingId,
         vesteeWalletNew,
       })
+      .remainingAccounts(registryAccounts)
       .signers(signers)
       .rpc();
   }
 
+  // the vestee wallets of the tests are owned by the provider wallet
+  public async requestVesteeWalletChange(
+    vesteeWalletNew: PublicKey,
+    vesteeKeypair: Keypair = payer
+  ) {
+    const registryAccounts = (await this.fetch()).immediateVesteeWalletChange
+      ? await this.vesteeRegistryMoveAccounts(vesteeWalletNew)
+      : [];
+
+    await vesting.methods
+      .requestVesteeWalletChange()
+      .accounts({
+        vestee: vesteeKeypair.publicKey,
+        vesting: this.id,
+        vesteeWalletNew,
+      })
+      .remainingAccounts(registryAccounts)
+      .signers([vesteeKeypair])
+      .rpc();
+  }
+
+  public async approveVesteeWalletChange(
+    walletManagerKeypair: Keypair,
+    vesteeWalletNew: PublicKey
+  ) {
+    await vesting.methods
+      .approveVesteeWalletChange()
+      .accounts({
+        walletManager: walletManagerKeypair.publicKey,
+        vesting: this.id,
+        vesteeWalletNew,
+      })
+      .remainingAccounts(await this.vesteeRegistryMoveAccounts(vesteeWalletNew))
+      .signers([walletManagerKeypair])
+      .rpc();
+  }
+
+  // the registry pages between which the schedule moves if the vestee wallet
+  // changes to the given one
+  public async vesteeRegistryMoveAccounts(
+    vesteeWalletNew: PublicKey
+  ): Promise<AccountMeta[]> {
+    return Vesting.registryMoveAccounts(
+      this.id,
+      "vestee",
+      await Vesting.walletOwnerOrPayer(vesteeWalletNew)
+    );
+  }
+
   public async updateVestedTokens(
     input: Partial<UpdateVestedTokensArgs> = {},
     ) {
-    const vestingKeypair = input.vestingKeypair ?? this.keypair;
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
 
     await vesting.methods
       .updateVestedTokens()
       .accounts({
-        vesting: vestingKeypair.publicKey,
+        vesting: vestingId,
       })
       .rpc();
   }
@@ -264,7 +818,7 @@ export class Vesting {
     input: Partial<FundVestingVault> = {},
     fundingAmount: number,
     ) {
-    const vestingKeypair = input.vestingKeypair ?? this.keypair;
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
     const walletAuthority = input.walletAuthority ?? Keypair.generate();
     const vestingVault = input.vestingVault ?? await this.vestingVault();
     const fundingWallet = input.fundingWallet ??
@@ -279,7 +833,7 @@ export class Vesting {
     await vesting.methods
       .fundVestingVault({amount: new BN(fundingAmount)})
       .accounts({
-        vesting: vestingKeypair.publicKey,
+        vesting: vestingId,
         vestingVault,
         walletAuthority: walletAuthority.publicKey,
         fundingWallet,
@@ -293,12 +847,43 @@ export class Vesting {
     input: Partial<WithdrawVestedTokens> = {},
     withdrawAmount: number,
   ) {
-    const vestingKeypair = input.vestingKeypair ?? this.keypair;
+    await vesting.methods
+      .withdrawVestedTokens({amount: new BN(withdrawAmount)})
+      .accounts(await this.withdrawAccounts(input))
+      .remainingAccounts(await this.withdrawRemainingAccounts(input))
+      .signers(await this.withdrawSigners(input))
+      .rpc();
+  }
+
+  public async withdrawMaxVestedTokens(
+    input: Partial<WithdrawVestedTokens> = {},
+  ): Promise<string> {
+    return vesting.methods
+      .withdrawMaxVestedTokens()
+      .accounts(await this.withdrawAccounts(input))
+      .remainingAccounts(await this.withdrawRemainingAccounts(input))
+      .signers(await this.withdrawSigners(input))
+      .rpc();
+  }
+
+  public async withdrawClaimableBalance(
+    input: Partial<WithdrawVestedTokens> = {},
+  ) {
+    await vesting.methods
+      .withdrawClaimableBalance()
+      .accounts(await this.withdrawAccounts(input))
+      .remainingAccounts(await this.withdrawRemainingAccounts(input))
+      .signers(await this.withdrawSigners(input))
+      .rpc();
+  }
+
+  private async withdrawAccounts(input: Partial<WithdrawVestedTokens>) {
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
     const vestingVault = input.vestingVault ?? await this.vestingVault();
     const vestingSignerPda =
     input.pda ??
     (await (async () => {
-      const [pda, _] = await Vesting.signerFrom(vestingKeypair.publicKey);
+      const [pda, _] = await Vesting.signerFrom(vestingId);
       return pda;
     })());
 
@@ -313,15 +898,249 @@ export class Vesting {
         return wallet;
     })());
 
+    return {
+      vesting: vestingId,
+      vestingVault,
+      vestingSigner: vestingSignerPda,
+      vesteeWallet,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  // by default the provider wallet signs, as anyone can withdraw unless
+  // the vesting is in the beneficiary only mode, and no signer is passed
+  // otherwise
+  private async withdrawAuthority(
+    input: Partial<WithdrawVestedTokens>
+  ): Promise<Keypair | undefined> {
+    const { beneficiaryOnly } = await this.fetch();
+    return beneficiaryOnly ? input.withdrawAuthority ?? payer : undefined;
+  }
+
+  private async withdrawSigners(input: Partial<WithdrawVestedTokens>) {
+    const withdrawAuthority = await this.withdrawAuthority(input);
+    return withdrawAuthority ? [withdrawAuthority] : [];
+  }
+
+  private async withdrawRemainingAccounts(
+    input: Partial<WithdrawVestedTokens>
+  ) {
+    const withdrawAuthority = await this.withdrawAuthority(input);
+    const authorityAccounts = withdrawAuthority
+      ? [
+          {
+            pubkey: withdrawAuthority.publicKey,
+            isSigner: true,
+            isWritable: false,
+          },
+        ]
+      : [];
+
+    if (!input.createVesteeWallet) {
+      return authorityAccounts;
+    }
+
+    const beneficiaryOwner = (await this.fetch()).beneficiaryOwner;
+    return [
+      ...authorityAccounts,
+      { pubkey: payer.publicKey, isSigner: true, isWritable: true },
+      { pubkey: beneficiaryOwner, isSigner: false, isWritable: false },
+      { pubkey: this.mint, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      {
+        pubkey: ASSOCIATED_TOKEN_PROGRAM_ID,
+        isSigner: false,
+        isWritable: false,
+      },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+    ];
+  }
+
+  public async setRoles(adminKeypair: Keypair, roles: Partial<VestingRoles>) {
     await vesting.methods
-      .withdrawVestedTokens({amount: new BN(withdrawAmount)})
+      .setRoles(Vesting.rolesFrom(roles))
       .accounts({
-        vesting: vestingKeypair.publicKey,
-        vestingVault,
-        vestingSigner: vestingSignerPda,
-        vesteeWallet,
+        admin: adminKeypair.publicKey,
+        vesting: this.id,
+      })
+      .signers([adminKeypair])
+      .rpc();
+  }
+
+  public async setTimelock(
+    adminKeypair: Keypair,
+    actionDelaySecs: number,
+    guardian: PublicKey = PublicKey.default
+  ) {
+    await vesting.methods
+      .setTimelock(new BN(actionDelaySecs), guardian)
+      .accounts({
+        admin: adminKeypair.publicKey,
+        vesting: this.id,
+      })
+      .signers([adminKeypair])
+      .rpc();
+  }
+
+  // the action is given as the anchor enum, e.g.
+  // { changeVesteeWallet: { vesteeWallet } }
+  public async queueAdminAction(
+    authorityKeypair: Keypair,
+    action: any
+  ): Promise<PublicKey> {
+    const queuedActionKeypair = Keypair.generate();
+
+    await vesting.methods
+      .queueAdminAction(action)
+      .accounts({
+        authority: authorityKeypair.publicKey,
+        vesting: this.id,
+        queuedAction: queuedActionKeypair.publicKey,
+        payer: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authorityKeypair, queuedActionKeypair])
+      .rpc();
+
+    return queuedActionKeypair.publicKey;
+  }
+
+  public async cancelAdminAction(
+    cancellerKeypair: Keypair,
+    queuedAction: PublicKey
+  ) {
+    await vesting.methods
+      .cancelAdminAction()
+      .accounts({
+        canceller: cancellerKeypair.publicKey,
+        vesting: this.id,
+        queuedAction,
+        rentReceiver: payer.publicKey,
+      })
+      .signers([cancellerKeypair])
+      .rpc();
+  }
+
+  public async executeAdminAction(
+    queuedAction: PublicKey,
+    remainingAccounts: PublicKey[] = []
+  ) {
+    await vesting.methods
+      .executeAdminAction()
+      .accounts({
+        vesting: this.id,
+        queuedAction,
+        rentReceiver: payer.publicKey,
+      })
+      .remainingAccounts(
+        remainingAccounts.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .rpc();
+  }
+
+  public async refundSurplus(
+    input: Partial<RefundSurplus> = {},
+    refundAmount: number,
+  ) {
+    const adminKeypair = input.adminKeypair ?? this.admin;
+    const refundWallet = input.refundWallet ??
+      (await createAccount(provider.connection, payer, this.mint, payer.publicKey));
+
+    await vesting.methods
+      .refundSurplus({amount: new BN(refundAmount)})
+      .accounts({
+        funder: adminKeypair.publicKey,
+        vesting: this.id,
+        vestingVault: await this.vestingVault(),
+        vestingSigner: await this.signerPda(),
+        refundWallet,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
+      .signers([adminKeypair])
+      .rpc();
+  }
+
+  public async proposeAdmin(adminKeypair: Keypair, pendingAdmin: PublicKey) {
+    await vesting.methods
+      .proposeAdmin(pendingAdmin)
+      .accounts({
+        admin: adminKeypair.publicKey,
+        vesting: this.id,
+      })
+      .signers([adminKeypair])
+      .rpc();
+  }
+
+  public async acceptAdmin(pendingAdminKeypair: Keypair) {
+    await vesting.methods
+      .acceptAdmin()
+      .accounts({
+        pendingAdmin: pendingAdminKeypair.publicKey,
+        vesting: this.id,
+      })
+      .remainingAccounts(
+        await Vesting.registryMoveAccounts(
+          this.id,
+          "admin",
+          pendingAdminKeypair.publicKey
+        )
+      )
+      .signers([pendingAdminKeypair])
+      .rpc();
+  }
+
+  public static async rotateAdminBatch(
+    adminKeypair: Keypair,
+    newAdminKeypair: Keypair,
+    vestings: PublicKey[]
+  ) {
+    // each vesting account is followed by the registry pages its listing
+    // moves between, if any
+    const remainingAccounts: AccountMeta[] = [];
+    for (const pubkey of vestings) {
+      remainingAccounts.push({ pubkey, isWritable: true, isSigner: false });
+      remainingAccounts.push(
+        ...(await Vesting.registryMoveAccounts(
+          pubkey,
+          "admin",
+          newAdminKeypair.publicKey
+        ))
+      );
+    }
+
+    await vesting.methods
+      .rotateAdminBatch()
+      .accounts({
+        admin: adminKeypair.publicKey,
+        newAdmin: newAdminKeypair.publicKey,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([adminKeypair, newAdminKeypair])
+      .rpc();
+  }
+
+  public async syncVault(vestingVault?: PublicKey) {
+    await vesting.methods
+      .syncVault()
+      .accounts({
+        vesting: this.id,
+        vestingVault: vestingVault ?? await this.vestingVault(),
+      })
+      .rpc();
+  }
+
+  public async migrateVesting(vestingId: PublicKey = this.id) {
+    await vesting.methods
+      .migrateVesting()
+      .accounts({
+        payer: payer.publicKey,
+        vesting: vestingId,
+        systemProgram: SystemProgram.programId,
+      })
       .rpc();
   }
 
@@ -331,9 +1150,12 @@ export class Vesting {
     const adminKeypair = input.adminKeypair ?? Keypair.generate();
     await airdrop(adminKeypair.publicKey);
 
-    const vestingKeypair = input.vestingKeypair ?? this.keypair;
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
     const skipAdminSignature = input.skipAdminSignature ?? false;
 
+    const recoveryWallet = input.recoveryWallet ??
+      (await createAccount(provider.connection, payer, this.mint, payer.publicKey));
+
     const signers = [];
     if (!skipAdminSignature) {
       signers.push(adminKeypair);
@@ -341,11 +1163,150 @@ export class Vesting {
 
     await vesting.methods
       .closeVestingSchedule()
+      .accounts({
+        closer: adminKeypair.publicKey,
+        vesting: vestingId,
+        vestingVault: await this.vestingVault(),
+        vestingSigner: await this.signerPda(),
+        recoveryWallet,
+        rentReceiver: input.rentReceiver ?? payer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(await this.registryAccounts())
+      .signers(signers)
+      .rpc();
+  }
+
+  public async setBlackoutWindows(
+    input: Partial<SetBlackoutWindows> = {},
+    windows: BlackoutWindow[],
+    ) {
+    const adminKeypair = input.adminKeypair ?? Keypair.generate();
+    await airdrop(adminKeypair.publicKey);
+
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
+    const skipAdminSignature = input.skipAdminSignature ?? false;
+
+    const signers = [];
+    if (!skipAdminSignature) {
+      signers.push(adminKeypair);
+    }
+
+    await vesting.methods
+      .setBlackoutWindows(
+        windows.map(({ start, end }) => ({
+          start: { time: new BN(start) },
+          end: { time: new BN(end) },
+        }))
+      )
       .accounts({
         admin: adminKeypair.publicKey,
-        vesting: vestingKeypair.publicKey,
+        vesting: vestingId,
       })
       .signers(signers)
       .rpc();
   }
+
+  public async sweepUnclaimedTokens(
+    input: Partial<SweepUnclaimedTokens> = {},
+    ) {
+    const adminKeypair = input.adminKeypair ?? Keypair.generate();
+    await airdrop(adminKeypair.publicKey);
+
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
+    const vestingVault = input.vestingVault ?? await this.vestingVault();
+    const vestingSignerPda =
+    input.pda ??
+    (await (async () => {
+      const [pda, _] = await Vesting.signerFrom(vestingId);
+      return pda;
+    })());
+    const skipAdminSignature = input.skipAdminSignature ?? false;
+
+    const recoveryWallet = input.recoveryWallet ??
+      (await (async () => {
+        const wallet = await createAccount(
+          provider.connection,
+          payer,
+          this.mint,
+          payer.publicKey
+        )
+        return wallet;
+    })());
+
+    const signers = [];
+    if (!skipAdminSignature) {
+      signers.push(adminKeypair);
+    }
+
+    await vesting.methods
+      .sweepUnclaimedTokens()
+      .accounts({
+        revoker: adminKeypair.publicKey,
+        vesting: vestingId,
+        vestingVault,
+        vestingSigner: vestingSignerPda,
+        recoveryWallet,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers(signers)
+      .rpc();
+  }
+
+  public async setWithdrawalDelegate(
+    input: Partial<SetWithdrawalDelegate> = {},
+    delegate: PublicKey,
+    ) {
+    const vesteeKeypair = input.vesteeKeypair ?? payer;
+    const vestingId = input.vestingKeypair?.publicKey ?? this.id;
+
+    await vesting.methods
+      .setWithdrawalDelegate(delegate)
+      .accounts({
+        vestee: vesteeKeypair.publicKey,
+        vesting: vestingId,
+      })
+      .signers([vesteeKeypair])
+      .rpc();
+  }
+
+  public async freezeVesting(authorityKeypair: Keypair) {
+    await vesting.methods
+      .freezeVesting()
+      .accounts({
+        authority: authorityKeypair.publicKey,
+        vesting: this.id,
+      })
+      .signers([authorityKeypair])
+      .rpc();
+  }
+
+  public async unfreezeVesting(authorityKeypair: Keypair) {
+    await vesting.methods
+      .unfreezeVesting()
+      .accounts({
+        authority: authorityKeypair.publicKey,
+        vesting: this.id,
+      })
+      .signers([authorityKeypair])
+      .rpc();
+  }
+
+  public async setVesteeOperator(
+    vesteeKeypair: Keypair,
+    operator: VesteeOperator
+  ) {
+    await vesting.methods
+      .setVesteeOperator({
+        key: operator.key,
+        permissions: operator.permissions,
+        expiresAt: { time: new BN(operator.expiresAt) },
+      })
+      .accounts({
+        vestee: vesteeKeypair.publicKey,
+        vesting: this.id,
+      })
+      .signers([vesteeKeypair])
+      .rpc();
+  }
 }